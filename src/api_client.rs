@@ -1,6 +1,127 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Environment variable holding the key for the `openai` provider.
+pub const OPENAI_KEY_ENV: &str = "OPENAI_API_KEY";
+
+/// An inference service models can be requested from. Entries in
+/// `selected_model` and `fallback_models` pick one with a `provider:` prefix;
+/// bare model IDs use GitHub Models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    Github,
+    Openai,
+}
+
+impl Provider {
+    pub const ALL: &'static [Provider] = &[Provider::Github, Provider::Openai];
+
+    pub fn parse(name: &str) -> Result<Self> {
+        Provider::ALL
+            .iter()
+            .copied()
+            .find(|p| p.name() == name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown provider '{}' (expected one of: {})",
+                    name,
+                    Provider::ALL
+                        .iter()
+                        .map(|p| p.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Provider::Github => "github",
+            Provider::Openai => "openai",
+        }
+    }
+
+    fn chat_endpoint(&self) -> &'static str {
+        match self {
+            Provider::Github => "https://models.inference.ai.azure.com/chat/completions",
+            Provider::Openai => "https://api.openai.com/v1/chat/completions",
+        }
+    }
+}
+
+/// A model on a specific provider, written `provider:model` or just `model`
+/// for GitHub Models.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelRef {
+    pub provider: Provider,
+    pub model: String,
+}
+
+impl ModelRef {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        let (provider, model) = match spec.split_once(':') {
+            Some((prefix, model)) => (
+                Provider::parse(prefix).map_err(|e| anyhow!("{} in '{}'", e, spec))?,
+                model,
+            ),
+            None => (Provider::Github, spec),
+        };
+        if model.is_empty() {
+            return Err(anyhow!("Missing model name in '{}'", spec));
+        }
+        Ok(Self {
+            provider,
+            model: model.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ModelRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.provider {
+            Provider::Github => write!(f, "{}", self.model),
+            provider => write!(f, "{}:{}", provider.name(), self.model),
+        }
+    }
+}
+
+/// `selected_model` followed by `fallback_models`, without duplicates.
+pub fn model_chain(selected: &str, fallbacks: &[String]) -> Result<Vec<ModelRef>> {
+    let mut chain: Vec<ModelRef> = Vec::new();
+    for spec in std::iter::once(selected).chain(fallbacks.iter().map(String::as_str)) {
+        let model = ModelRef::parse(spec)?;
+        if !chain.contains(&model) {
+            chain.push(model);
+        }
+    }
+    Ok(chain)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("API request failed with status {status}: {body}")]
+    Status { status: u16, body: String },
+    #[error("API request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Unexpected API response format")]
+    InvalidResponse,
+}
+
+impl ApiError {
+    /// Failures another model may not share: rate limits, timeouts, server
+    /// errors and models the provider no longer serves. Authentication and
+    /// other request errors would fail the same way again.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ApiError::Status { status, .. } => matches!(status, 404 | 408 | 429 | 500..=599),
+            ApiError::Request(e) => e.is_timeout() || e.is_connect(),
+            ApiError::InvalidResponse => true,
+        }
+    }
+}
 
 #[derive(Debug, Serialize)]
 struct Message {
@@ -12,11 +133,40 @@ struct Message {
 struct ChatCompletionRequest {
     messages: Vec<Message>,
     model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+}
+
+/// Sampling settings sent with a request; unset values use the provider's
+/// defaults.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GenerationOptions {
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
 struct ChatCompletionResponse {
     choices: Option<Vec<Choice>>,
+    usage: Option<Usage>,
+}
+
+/// Token counts reported by the provider for one request.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Usage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub completion_tokens: u64,
+}
+
+/// A generated message with the provider's token counts, when reported.
+#[derive(Debug, Clone)]
+pub struct Completion {
+    pub content: String,
+    pub usage: Option<Usage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,26 +179,47 @@ struct ResponseMessage {
     content: Option<String>,
 }
 
-pub async fn call_inference_api(api_key: &str, prompt: &str, model: &str) -> Result<String> {
+pub const SYSTEM_PROMPT: &str = "You are a helpful assistant that generates concise, informative git commit messages. Reply only with the commit message, nothing else.";
+
+pub fn build_user_prompt(file_names: &str, compressed_json: &str) -> String {
+    format!(
+        "reply only with a very concise but informative commit message, and nothing else:\n\nFiles:\n{}\n\nSummaryJSON:{}",
+        file_names, compressed_json
+    )
+}
+
+/// Rough token estimate (~4 characters per token) used for previews; the
+/// real count depends on the model's tokenizer.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+pub async fn call_inference_api(
+    api_key: &str,
+    prompt: &str,
+    model: &ModelRef,
+    options: GenerationOptions,
+) -> Result<Completion, ApiError> {
     let client = Client::new();
-    let url = "https://models.inference.ai.azure.com/chat/completions";
 
     let request = ChatCompletionRequest {
         messages: vec![
             Message {
                 role: "system".to_string(),
-                content: "You are a helpful assistant that generates concise, informative git commit messages. Reply only with the commit message, nothing else.".to_string(),
+                content: SYSTEM_PROMPT.to_string(),
             },
             Message {
                 role: "user".to_string(),
                 content: prompt.to_string(),
             },
         ],
-        model: model.to_string(),
+        model: model.model.clone(),
+        temperature: options.temperature,
+        max_tokens: options.max_tokens,
     };
 
     let response = client
-        .post(url)
+        .post(model.provider.chat_endpoint())
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_key))
         .json(&request)
@@ -56,9 +227,9 @@ pub async fn call_inference_api(api_key: &str, prompt: &str, model: &str) -> Res
         .await?;
 
     if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        anyhow::bail!("API request failed with status {}: {}", status, text);
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(ApiError::Status { status, body });
     }
 
     let response_data: ChatCompletionResponse = response.json().await?;
@@ -67,25 +238,75 @@ pub async fn call_inference_api(api_key: &str, prompt: &str, model: &str) -> Res
         if let Some(choice) = choices.first() {
             if let Some(message) = &choice.message {
                 if let Some(content) = &message.content {
-                    return Ok(content.trim().to_string());
+                    return Ok(Completion {
+                        content: content.trim().to_string(),
+                        usage: response_data.usage,
+                    });
                 }
             }
         }
     }
 
-    anyhow::bail!("Unexpected API response format")
+    Err(ApiError::InvalidResponse)
 }
 
 pub async fn generate_commit_message(
     api_key: &str,
     file_names: &str,
     compressed_json: &str,
-    model: &str,
-) -> Result<String> {
-    let prompt = format!(
-        "reply only with a very concise but informative commit message, and nothing else:\n\nFiles:\n{}\n\nSummaryJSON:{}",
-        file_names, compressed_json
-    );
+    model: &ModelRef,
+    options: GenerationOptions,
+) -> Result<String, ApiError> {
+    let prompt = build_user_prompt(file_names, compressed_json);
+
+    call_inference_api(api_key, &prompt, model, options)
+        .await
+        .map(|completion| completion.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_chain() {
+        let chain = model_chain(
+            "gpt-4o",
+            &[
+                "gpt-4o-mini".to_string(),
+                "openai:gpt-4o-mini".to_string(),
+                "github:gpt-4o".to_string(),
+            ],
+        )
+        .unwrap();
+        let names: Vec<String> = chain.iter().map(|m| m.to_string()).collect();
+        assert_eq!(names, vec!["gpt-4o", "gpt-4o-mini", "openai:gpt-4o-mini"]);
+        assert!(ModelRef::parse("azure:gpt-4o").is_err());
+        assert!(ModelRef::parse("openai:").is_err());
+    }
 
-    call_inference_api(api_key, &prompt, model).await
+    #[test]
+    fn test_request_omits_unset_options() {
+        let request = ChatCompletionRequest {
+            messages: vec![],
+            model: "gpt-4o".to_string(),
+            temperature: Some(0.2),
+            max_tokens: None,
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["temperature"], serde_json::json!(0.2f32));
+        assert!(json.get("max_tokens").is_none());
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        let status = |status| ApiError::Status {
+            status,
+            body: String::new(),
+        };
+        assert!(status(429).is_retryable());
+        assert!(status(503).is_retryable());
+        assert!(!status(401).is_retryable());
+        assert!(!status(400).is_retryable());
+    }
 }
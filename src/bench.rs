@@ -0,0 +1,226 @@
+use crate::api_client::Usage;
+use serde::Serialize;
+
+/// Default number of commits replayed by `bench`.
+pub const DEFAULT_BENCH_COMMITS: usize = 5;
+
+/// One model's attempt at one commit.
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchResult {
+    pub model: String,
+    pub message: Option<String>,
+    pub error: Option<String>,
+    pub latency_ms: Option<u64>,
+    pub usage: Option<Usage>,
+    /// USD, when the model's prices are known.
+    pub estimated_cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchCommit {
+    pub sha: String,
+    /// The message the commit was actually made with.
+    pub original: String,
+    pub results: Vec<BenchResult>,
+}
+
+/// Totals for one model across all commits.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelSummary {
+    pub model: String,
+    pub runs: usize,
+    pub failures: usize,
+    pub avg_latency_ms: Option<u64>,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub estimated_cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub repository: String,
+    pub models: Vec<String>,
+    pub summary: Vec<ModelSummary>,
+    pub commits: Vec<BenchCommit>,
+}
+
+impl BenchReport {
+    /// Builds the report, computing `summary` from the per-commit results.
+    pub fn new(repository: String, models: Vec<String>, commits: Vec<BenchCommit>) -> Self {
+        let summary = models
+            .iter()
+            .map(|model| summarize(model, &commits))
+            .collect();
+        Self {
+            repository,
+            models,
+            summary,
+            commits,
+        }
+    }
+}
+
+fn summarize(model: &str, commits: &[BenchCommit]) -> ModelSummary {
+    let results: Vec<&BenchResult> = commits
+        .iter()
+        .flat_map(|c| &c.results)
+        .filter(|r| r.model == model)
+        .collect();
+    let succeeded: Vec<&BenchResult> = results
+        .iter()
+        .copied()
+        .filter(|r| r.message.is_some())
+        .collect();
+    let latencies: Vec<u64> = succeeded.iter().filter_map(|r| r.latency_ms).collect();
+    let costs: Vec<f64> = succeeded.iter().filter_map(|r| r.estimated_cost).collect();
+
+    ModelSummary {
+        model: model.to_string(),
+        runs: results.len(),
+        failures: results.len() - succeeded.len(),
+        avg_latency_ms: (!latencies.is_empty())
+            .then(|| latencies.iter().sum::<u64>() / latencies.len() as u64),
+        prompt_tokens: succeeded
+            .iter()
+            .filter_map(|r| r.usage)
+            .map(|u| u.prompt_tokens)
+            .sum(),
+        completion_tokens: succeeded
+            .iter()
+            .filter_map(|r| r.usage)
+            .map(|u| u.completion_tokens)
+            .sum(),
+        estimated_cost: (!costs.is_empty()).then(|| costs.iter().sum()),
+    }
+}
+
+/// Makes `text` safe inside a Markdown table cell.
+fn cell(text: &str) -> String {
+    text.trim()
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+fn or_dash<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "–".to_string(), |v| v.to_string())
+}
+
+/// The report as Markdown: a per-model summary table, then one table per
+/// commit with the original message next to each model's.
+pub fn render_markdown(report: &BenchReport) -> String {
+    let mut md = String::new();
+    md.push_str("# Model benchmark\n\n");
+    md.push_str(&format!(
+        "Repository: `{}` · {} commit(s) · {} model(s)\n\n",
+        report.repository,
+        report.commits.len(),
+        report.models.len()
+    ));
+
+    md.push_str("## Summary\n\n");
+    md.push_str("| Model | Runs | Failures | Avg latency | Prompt tokens | Completion tokens | Est. cost |\n");
+    md.push_str("|---|---|---|---|---|---|---|\n");
+    for s in &report.summary {
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} |\n",
+            cell(&s.model),
+            s.runs,
+            s.failures,
+            or_dash(s.avg_latency_ms.map(|ms| format!("{} ms", ms))),
+            s.prompt_tokens,
+            s.completion_tokens,
+            or_dash(s.estimated_cost.map(crate::model_manager::format_cost)),
+        ));
+    }
+
+    md.push_str("\n## Commits\n");
+    for commit in &report.commits {
+        let subject = commit.original.lines().next().unwrap_or_default();
+        md.push_str(&format!(
+            "\n### {} {}\n\n",
+            &commit.sha[..commit.sha.len().min(7)],
+            cell(subject)
+        ));
+        md.push_str("| Source | Message | Latency | Tokens |\n");
+        md.push_str("|---|---|---|---|\n");
+        md.push_str(&format!(
+            "| original | {} | – | – |\n",
+            cell(&commit.original)
+        ));
+        for r in &commit.results {
+            let message = match (&r.message, &r.error) {
+                (Some(message), _) => cell(message),
+                (None, Some(error)) => format!("_error: {}_", cell(error)),
+                (None, None) => "–".to_string(),
+            };
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                cell(&r.model),
+                message,
+                or_dash(r.latency_ms.map(|ms| format!("{} ms", ms))),
+                or_dash(r.usage.map(|u| u.prompt_tokens + u.completion_tokens)),
+            ));
+        }
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(model: &str, message: Option<&str>, latency_ms: u64) -> BenchResult {
+        BenchResult {
+            model: model.to_string(),
+            message: message.map(str::to_string),
+            error: message.is_none().then(|| "429 | rate limited".to_string()),
+            latency_ms: Some(latency_ms),
+            usage: message.map(|_| Usage {
+                prompt_tokens: 100,
+                completion_tokens: 10,
+            }),
+            estimated_cost: None,
+        }
+    }
+
+    #[test]
+    fn test_report_summary_and_markdown() {
+        let commits = vec![
+            BenchCommit {
+                sha: "0123456789abcdef".to_string(),
+                original: "fix: handle | in names\n\nLonger body".to_string(),
+                results: vec![
+                    result("gpt-4o", Some("fix: escape pipes"), 300),
+                    result("gpt-4o-mini", None, 50),
+                ],
+            },
+            BenchCommit {
+                sha: "fedcba9876543210".to_string(),
+                original: "docs: update usage".to_string(),
+                results: vec![
+                    result("gpt-4o", Some("docs: refresh usage guide"), 500),
+                    result("gpt-4o-mini", Some("docs: update"), 100),
+                ],
+            },
+        ];
+        let report = BenchReport::new(
+            "/repo".to_string(),
+            vec!["gpt-4o".to_string(), "gpt-4o-mini".to_string()],
+            commits,
+        );
+
+        let gpt4o = &report.summary[0];
+        assert_eq!((gpt4o.runs, gpt4o.failures), (2, 0));
+        assert_eq!(gpt4o.avg_latency_ms, Some(400));
+        assert_eq!(gpt4o.prompt_tokens, 200);
+        let mini = &report.summary[1];
+        assert_eq!((mini.runs, mini.failures), (2, 1));
+        assert_eq!(mini.avg_latency_ms, Some(100));
+
+        let md = render_markdown(&report);
+        assert!(md.contains("### 0123456 fix: handle \\| in names"));
+        assert!(md.contains("| original | fix: handle \\| in names<br><br>Longer body | – | – |"));
+        assert!(md.contains("| gpt-4o-mini | _error: 429 \\| rate limited_ | 50 ms | – |"));
+    }
+}
@@ -19,21 +19,8 @@ pub async fn analyze_file_change(cwd: &str, file: &str) -> Result<String> {
                 return Ok("unchanged".to_string());
             }
 
-            if let Some(first_line) = diff.lines().next() {
-                let parts: Vec<&str> = first_line.split('\t').collect();
-                if parts.len() >= 3 {
-                    let added = if parts[0] == "-" {
-                        0
-                    } else {
-                        parts[0].parse::<usize>().unwrap_or(0)
-                    };
-                    let removed = if parts[1] == "-" {
-                        0
-                    } else {
-                        parts[1].parse::<usize>().unwrap_or(0)
-                    };
-                    return Ok(format!("{}+/{}−", added, removed));
-                }
+            if let Some((_, change)) = diff.lines().next().and_then(parse_numstat_line) {
+                return Ok(change);
             }
 
             Ok("mod".to_string())
@@ -48,7 +35,7 @@ pub async fn analyze_file_change(cwd: &str, file: &str) -> Result<String> {
                 let truncated = &first[..std::cmp::min(40, first.len())];
                 let collapsed = Regex::new(r"\s+")
                     .ok()
-                    .and_then(|r| Some(r.replace_all(truncated, " ").to_string()))
+                    .map(|r| r.replace_all(truncated, " ").to_string())
                     .unwrap_or_else(|| truncated.to_string());
                 Ok(collapsed)
             }
@@ -57,6 +44,31 @@ pub async fn analyze_file_change(cwd: &str, file: &str) -> Result<String> {
     }
 }
 
+/// File and `5+/2−`-style descriptor from one `--numstat` line. Binary
+/// files (`-` counts) are reported as `0+/0−`.
+fn parse_numstat_line(line: &str) -> Option<(String, String)> {
+    let parts: Vec<&str> = line.splitn(3, '\t').collect();
+    let [added, removed, file] = parts.as_slice() else {
+        return None;
+    };
+    let count = |n: &str| n.parse::<usize>().unwrap_or(0);
+    Some((
+        file.to_string(),
+        format!("{}+/{}−", count(added), count(removed)),
+    ))
+}
+
+/// The changes `sha` made, described like staged changes, for replaying old
+/// commits.
+pub fn build_commit_file_changes(cwd: &str, sha: &str) -> Result<Vec<FileChange>> {
+    let numstat = crate::git::get_commit_numstat(cwd, sha)?;
+    Ok(numstat
+        .lines()
+        .filter_map(parse_numstat_line)
+        .map(|(file, change)| FileChange { file, change })
+        .collect())
+}
+
 pub async fn build_file_changes(cwd: &str) -> Result<Vec<FileChange>> {
     let files = get_staged_files(cwd).await?;
     let mut changes = Vec::new();
@@ -69,6 +81,29 @@ pub async fn build_file_changes(cwd: &str) -> Result<Vec<FileChange>> {
     Ok(changes)
 }
 
+/// Summary size, in characters, used when the model's limits are unknown.
+pub const DEFAULT_SUMMARY_BUDGET: usize = 400;
+
+/// Largest summary sent regardless of context window; a commit message
+/// needs only an overview of the change.
+pub const MAX_SUMMARY_BUDGET: usize = 8000;
+
+/// Characters of summary JSON to send to a model: a quarter of the input
+/// tokens left after reserving its output (at ~4 characters per token),
+/// kept between `DEFAULT_SUMMARY_BUDGET` and `MAX_SUMMARY_BUDGET`.
+pub fn summary_budget(context_window: Option<u64>, max_output_tokens: Option<u64>) -> usize {
+    let Some(window) = context_window else {
+        return DEFAULT_SUMMARY_BUDGET;
+    };
+    let reserved = max_output_tokens.unwrap_or(0).min(window / 2);
+    let input_tokens = window - reserved;
+    usize::try_from(input_tokens)
+        .unwrap_or(usize::MAX)
+        .clamp(DEFAULT_SUMMARY_BUDGET, MAX_SUMMARY_BUDGET)
+}
+
+type ChangeMap = Box<dyn Fn(&str) -> String>;
+
 pub fn compress_to_json(file_changes: &[FileChange], max_len: usize) -> String {
     if file_changes.is_empty() {
         return r#"{"files":[]}"#.to_string();
@@ -95,7 +130,7 @@ pub fn compress_to_json(file_changes: &[FileChange], max_len: usize) -> String {
         format!(r#"{{"files":[{}]}}"#, items.join(","))
     };
 
-    let maps: Vec<Box<dyn Fn(&str) -> String>> = vec![
+    let maps: Vec<ChangeMap> = vec![
         Box::new(|c: &str| c.to_string()),
         Box::new(|c: &str| c[..std::cmp::min(12, c.len())].to_string()),
         Box::new(|c: &str| c[..std::cmp::min(6, c.len())].to_string()),
@@ -117,7 +152,12 @@ pub fn compress_to_json(file_changes: &[FileChange], max_len: usize) -> String {
         .iter()
         .take(1)
         .map(|fc| {
-            let filename = fc.file.split('/').last().unwrap_or(&fc.file).to_string();
+            let filename = fc
+                .file
+                .split('/')
+                .next_back()
+                .unwrap_or(&fc.file)
+                .to_string();
             FileChange {
                 file: filename,
                 change: "mod".to_string(),
@@ -148,4 +188,31 @@ mod tests {
         assert!(result.contains("main.rs"));
         assert!(result.contains("5+/2−"));
     }
+
+    #[test]
+    fn test_parse_numstat_line() {
+        assert_eq!(
+            parse_numstat_line("5\t2\tsrc/main.rs"),
+            Some(("src/main.rs".to_string(), "5+/2−".to_string()))
+        );
+        assert_eq!(
+            parse_numstat_line("-\t-\tlogo.png"),
+            Some(("logo.png".to_string(), "0+/0−".to_string()))
+        );
+        assert_eq!(parse_numstat_line("garbage"), None);
+    }
+
+    #[test]
+    fn test_summary_budget() {
+        assert_eq!(summary_budget(None, None), DEFAULT_SUMMARY_BUDGET);
+        assert_eq!(summary_budget(Some(4096), Some(1024)), 3072);
+        assert_eq!(
+            summary_budget(Some(600), Some(4096)),
+            DEFAULT_SUMMARY_BUDGET
+        );
+        assert_eq!(
+            summary_budget(Some(128_000), Some(16_384)),
+            MAX_SUMMARY_BUDGET
+        );
+    }
 }
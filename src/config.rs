@@ -1,21 +1,61 @@
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Committed per-repository config files, checked in this order.
+pub const REPO_CONFIG_FILES: &[&str] = &[".autocommiter.toml", ".autocommiter.json"];
+
+/// Untracked per-clone override, stored inside the git directory.
+pub const LOCAL_CONFIG_FILE: &str = "autocommiter.json";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Schema version of the file; see `CONFIG_VERSION`.
+    pub version: Option<u64>,
     pub api_key: Option<String>,
     pub selected_model: Option<String>,
     pub enable_gitmoji: Option<bool>,
     pub update_gitignore: Option<bool>,
     pub skip_confirmation: Option<bool>,
     pub gitignore_patterns: Option<Vec<String>>,
+    pub secret_allowlist: Option<Vec<String>>,
+    pub redact_patterns: Option<Vec<String>>,
+    pub privacy_mode: Option<String>,
+    pub tracked_ignored_policy: Option<String>,
+    /// Tracked files (gitignore-style patterns) that match
+    /// `gitignore_patterns` but are kept on purpose, such as a committed
+    /// `.envrc`.
+    pub tracked_ignored_allowlist: Option<Vec<String>>,
+    pub max_file_size_kb: Option<u64>,
+    pub allow_large_files: Option<bool>,
+    pub allow_binary_files: Option<bool>,
+    pub api_key_file: Option<String>,
+    pub api_key_command: Option<String>,
+    pub use_git_credential: Option<bool>,
+    /// Age after which the cached model list is refreshed; 0 disables
+    /// automatic refreshes.
+    pub model_cache_ttl_hours: Option<u64>,
+    /// Models tried in order after `selected_model` when a request fails
+    /// with a retryable error; `provider:model` selects another provider.
+    pub fallback_models: Option<Vec<String>>,
+    /// Short names for models, such as `fast = "gpt-4o-mini"`, accepted
+    /// wherever a model is named.
+    pub model_aliases: Option<BTreeMap<String, String>>,
+    /// Profile applied when neither `--profile` nor `AUTOCOMMITER_PROFILE`
+    /// names one; typically set per repository.
+    pub profile: Option<String>,
+    /// Named sets of settings layered over the global config.
+    pub profiles: Option<BTreeMap<String, Map<String, Value>>>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            version: Some(CONFIG_VERSION),
             api_key: None,
             selected_model: Some("gpt-4o-mini".to_string()),
             enable_gitmoji: Some(false),
@@ -24,81 +64,1184 @@ impl Default for Config {
             gitignore_patterns: Some(vec![
                 "*.env*".to_string(),
                 ".env*".to_string(),
+                "!.env.example".to_string(),
+                "!.env.sample".to_string(),
                 "docx/".to_string(),
                 ".docx/".to_string(),
             ]),
+            secret_allowlist: None,
+            redact_patterns: None,
+            privacy_mode: Some("placeholder".to_string()),
+            tracked_ignored_policy: Some("prompt".to_string()),
+            tracked_ignored_allowlist: None,
+            max_file_size_kb: Some(crate::file_guard::DEFAULT_MAX_FILE_SIZE_KB),
+            allow_large_files: Some(false),
+            allow_binary_files: Some(false),
+            api_key_file: None,
+            api_key_command: None,
+            use_git_credential: Some(false),
+            model_cache_ttl_hours: Some(crate::model_manager::DEFAULT_CACHE_TTL_HOURS),
+            fallback_models: None,
+            model_aliases: None,
+            profile: None,
+            profiles: None,
         }
     }
 }
 
+impl Config {
+    /// The model `name` refers to: the target of its `model_aliases` entry,
+    /// or `name` itself. Aliases are not resolved recursively.
+    pub fn resolve_model(&self, name: &str) -> String {
+        self.model_aliases
+            .as_ref()
+            .and_then(|aliases| aliases.get(name))
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// `selected_model` with its alias resolved.
+    pub fn effective_model(&self) -> String {
+        self.resolve_model(self.selected_model.as_deref().unwrap_or("gpt-4o-mini"))
+    }
+
+    /// `fallback_models` with aliases resolved.
+    pub fn effective_fallback_models(&self) -> Vec<String> {
+        self.fallback_models
+            .iter()
+            .flatten()
+            .map(|name| self.resolve_model(name))
+            .collect()
+    }
+
+    /// Aliases pointing at `model`.
+    pub fn aliases_for(&self, model: &str) -> Vec<String> {
+        self.model_aliases
+            .iter()
+            .flatten()
+            .filter(|(_, target)| *target == model)
+            .map(|(alias, _)| alias.clone())
+            .collect()
+    }
+}
+
+/// Global config file name inside the config directory.
+pub const GLOBAL_CONFIG_FILE: &str = "config.json";
+
+/// Global config location before the move to the XDG config directory.
+const LEGACY_CONFIG_FILE: &str = ".autocommiter.json";
+
+/// `AUTOCOMMITER_CONFIG` if set, otherwise `config.json` in the XDG config
+/// directory. A legacy `~/.autocommiter.json` is moved there on first use.
 pub fn get_config_file() -> Result<PathBuf> {
-    let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not determine home directory"))?;
-    Ok(home.join(".autocommiter.json"))
+    if let Some(path) = std::env::var_os(crate::paths::CONFIG_ENV_VAR).filter(|p| !p.is_empty()) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let path = crate::paths::config_dir()?.join(GLOBAL_CONFIG_FILE);
+    crate::paths::migrate_legacy_file(&crate::paths::home_dir()?.join(LEGACY_CONFIG_FILE), &path)?;
+    Ok(path)
 }
 
 pub fn load_config() -> Result<Config> {
-    let config_file = get_config_file()?;
+    load_config_from(&get_config_file()?)
+}
+
+/// The defaults overridden by the global file, without any other layer.
+pub fn load_global_config() -> Result<Config> {
+    load_global_config_from(&get_config_file()?)
+}
+
+fn load_global_config_from(path: &Path) -> Result<Config> {
+    let mut merged = Map::new();
+    let mut sources = BTreeMap::new();
+    if let Value::Object(defaults) = serde_json::to_value(Config::default())? {
+        merge_layer(&mut merged, &mut sources, defaults, ConfigSource::Default);
+    }
+    if let Value::Object(global) = serde_json::to_value(load_config_from(path)?)? {
+        merge_layer(&mut merged, &mut sources, global, ConfigSource::Global);
+    }
+    serde_json::from_value(Value::Object(merged))
+        .map_err(|e| anyhow!("Invalid configuration value: {}", e))
+}
 
-    if !config_file.exists() {
+fn json_parse_error(path: &Path, e: &serde_json::Error) -> anyhow::Error {
+    let location = format!(" at line {} column {}", e.line(), e.column());
+    let message = e.to_string();
+    anyhow!(
+        "Invalid config file {} (line {}, column {}): {}. Fix or remove the file; it will not be overwritten.",
+        path.display(),
+        e.line(),
+        e.column(),
+        message.strip_suffix(&location).unwrap_or(&message)
+    )
+}
+
+fn load_config_from(path: &Path) -> Result<Config> {
+    if !path.exists() {
         return Ok(Config::default());
     }
 
-    let content = fs::read_to_string(&config_file)?;
-    let config = serde_json::from_str(&content).unwrap_or_default();
-    Ok(config)
+    let layer = load_layer(path)?;
+    serde_json::from_value(Value::Object(layer))
+        .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))
+}
+
+/// Where an effective config value came from. Later layers win:
+/// default < token env < global < profile < repo < local < env.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    Default,
+    /// Standard token variables such as `GITHUB_TOKEN`, used only when no
+    /// config file sets a key.
+    TokenEnv,
+    Global,
+    /// The active entry of `profiles`.
+    Profile,
+    Repo,
+    Local,
+    /// `AUTOCOMMITER_*` variables.
+    Env,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ConfigSource::Default => "default",
+            ConfigSource::TokenEnv => "token env",
+            ConfigSource::Global => "global",
+            ConfigSource::Profile => "profile",
+            ConfigSource::Repo => "repo",
+            ConfigSource::Local => "local",
+            ConfigSource::Env => "env",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+pub const PRECEDENCE: &str =
+    "default < GITHUB_TOKEN/GH_TOKEN < global < profile < repo < local < AUTOCOMMITER_* env";
+
+/// Prefix for environment variable overrides, e.g. `AUTOCOMMITER_MODEL`.
+pub const ENV_PREFIX: &str = "AUTOCOMMITER_";
+
+/// Standard token variables accepted as a fallback API key, in order.
+pub const TOKEN_ENV_VARS: &[&str] = &["GITHUB_TOKEN", "GH_TOKEN"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    String,
+    Bool,
+    Integer,
+    List,
+    /// A JSON object, such as `profiles`.
+    Table,
+}
+
+/// Every `Config` field with its value type.
+pub const FIELDS: &[(&str, FieldKind)] = &[
+    ("version", FieldKind::Integer),
+    ("api_key", FieldKind::String),
+    ("selected_model", FieldKind::String),
+    ("enable_gitmoji", FieldKind::Bool),
+    ("update_gitignore", FieldKind::Bool),
+    ("skip_confirmation", FieldKind::Bool),
+    ("gitignore_patterns", FieldKind::List),
+    ("secret_allowlist", FieldKind::List),
+    ("redact_patterns", FieldKind::List),
+    ("privacy_mode", FieldKind::String),
+    ("tracked_ignored_policy", FieldKind::String),
+    ("tracked_ignored_allowlist", FieldKind::List),
+    ("max_file_size_kb", FieldKind::Integer),
+    ("allow_large_files", FieldKind::Bool),
+    ("allow_binary_files", FieldKind::Bool),
+    ("api_key_file", FieldKind::String),
+    ("api_key_command", FieldKind::String),
+    ("use_git_credential", FieldKind::Bool),
+    ("model_cache_ttl_hours", FieldKind::Integer),
+    ("fallback_models", FieldKind::List),
+    ("model_aliases", FieldKind::Table),
+    ("profile", FieldKind::String),
+    ("profiles", FieldKind::Table),
+];
+
+/// Fields never read from the committed repo file: it is shared with
+/// everyone who clones the repo, and a key command there would run
+/// arbitrary code (as would a credential helper it enables), while
+/// `skip_confirmation` would commit and push without asking.
+const REPO_IGNORED_FIELDS: &[&str] = &[
+    "api_key",
+    "api_key_file",
+    "api_key_command",
+    "use_git_credential",
+    "skip_confirmation",
+];
+
+/// Safety settings the repo file may only tighten. Anyone who clones the
+/// repo gets its file, so it must not be able to allowlist secrets, turn off
+/// the large/binary checks or untrack files on their behalf.
+const TIGHTEN_ONLY_FIELDS: &[&str] = &[
+    "gitignore_patterns",
+    "secret_allowlist",
+    "redact_patterns",
+    "privacy_mode",
+    "tracked_ignored_policy",
+    "tracked_ignored_allowlist",
+    "max_file_size_kb",
+    "allow_large_files",
+    "allow_binary_files",
+];
+
+/// Short aliases accepted for environment variables in addition to the
+/// `AUTOCOMMITER_<FIELD>` form.
+const ENV_ALIASES: &[(&str, &str)] = &[("MODEL", "selected_model"), ("GITMOJI", "enable_gitmoji")];
+
+pub fn field_kind(field: &str) -> Option<FieldKind> {
+    FIELDS
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map(|(_, kind)| *kind)
+}
+
+pub fn env_var_name(field: &str) -> String {
+    format!("{}{}", ENV_PREFIX, field.to_uppercase())
+}
+
+/// Parses a string (from the environment or the command line) into the JSON
+/// value for a field of the given kind. Lists are comma-separated or a JSON
+/// array.
+pub fn parse_field_value(kind: FieldKind, raw: &str) -> Result<Value> {
+    let raw = raw.trim();
+    match kind {
+        FieldKind::String => Ok(Value::String(raw.to_string())),
+        FieldKind::Bool => match raw.to_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(Value::Bool(true)),
+            "0" | "false" | "no" | "off" => Ok(Value::Bool(false)),
+            _ => Err(anyhow!("expected a boolean (true/false), got '{}'", raw)),
+        },
+        FieldKind::Integer => raw
+            .parse::<u64>()
+            .map(Value::from)
+            .map_err(|_| anyhow!("expected a non-negative integer, got '{}'", raw)),
+        FieldKind::List => {
+            if raw.starts_with('[') {
+                let items: Vec<String> = serde_json::from_str(raw)
+                    .map_err(|e| anyhow!("expected a JSON array of strings: {}", e))?;
+                return Ok(Value::from(items));
+            }
+            Ok(Value::from(
+                raw.split(',')
+                    .map(|s| s.trim())
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>(),
+            ))
+        }
+        FieldKind::Table => match serde_json::from_str(raw) {
+            Ok(Value::Object(map)) => Ok(Value::Object(map)),
+            _ => Err(anyhow!("expected a JSON object, got '{}'", raw)),
+        },
+    }
+}
+
+/// Splits a `profiles.<name>.<field>` key into its profile and field names.
+pub fn split_profile_key(key: &str) -> Option<(&str, &str)> {
+    let rest = key.strip_prefix("profiles.")?;
+    let (name, field) = rest.rsplit_once('.')?;
+    (!name.is_empty()).then_some((name, field))
+}
+
+/// Like `require_field`, but also accepts `profiles.<name>.<field>` for a
+/// single setting inside a profile.
+pub fn require_key(key: &str) -> Result<FieldKind> {
+    match split_profile_key(key) {
+        Some((_, "profile" | "profiles")) => Err(anyhow!("Profiles cannot be nested")),
+        Some((_, field)) => require_field(field),
+        None => require_field(key),
+    }
+}
+
+/// Like `require_key`, for the keys `config set/unset/add/remove` may
+/// change. `version` is only ever written by migrations.
+pub fn require_writable_key(key: &str) -> Result<FieldKind> {
+    let field = split_profile_key(key).map_or(key, |(_, field)| field);
+    if field == "version" {
+        return Err(anyhow!(
+            "version is set automatically whenever a config file is written and cannot be changed"
+        ));
+    }
+    require_key(key)
 }
 
-pub fn save_config(config: &Config) -> Result<()> {
-    let config_file = get_config_file()?;
-    let content = serde_json::to_string_pretty(&config)?;
-    fs::write(&config_file, content)?;
+/// Value of a top-level or `profiles.<name>.<field>` key.
+pub fn get_key<'a>(map: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    match split_profile_key(key) {
+        Some((name, field)) => map.get("profiles")?.get(name)?.get(field),
+        None => map.get(key),
+    }
+    .filter(|value| !value.is_null())
+}
+
+pub fn set_key(map: &mut Map<String, Value>, key: &str, value: Value) {
+    let Some((name, field)) = split_profile_key(key) else {
+        map.insert(key.to_string(), value);
+        return;
+    };
+    let profiles = map
+        .entry("profiles")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !profiles.is_object() {
+        *profiles = Value::Object(Map::new());
+    }
+    let profile = profiles
+        .as_object_mut()
+        .map(|p| p.entry(name).or_insert_with(|| Value::Object(Map::new())));
+    if let Some(Value::Object(profile)) = profile {
+        profile.insert(field.to_string(), value);
+    }
+}
+
+/// Removes a key. Profiles left without settings are removed too.
+pub fn remove_key(map: &mut Map<String, Value>, key: &str) {
+    let Some((name, field)) = split_profile_key(key) else {
+        map.remove(key);
+        return;
+    };
+    let Some(Value::Object(profiles)) = map.get_mut("profiles") else {
+        return;
+    };
+    if let Some(Value::Object(profile)) = profiles.get_mut(name) {
+        profile.remove(field);
+        if profile.is_empty() {
+            profiles.remove(name);
+        }
+    }
+    if profiles.is_empty() {
+        map.remove("profiles");
+    }
+}
+
+/// Collects `AUTOCOMMITER_*` overrides from the process environment.
+fn env_layer() -> Result<Map<String, Value>> {
+    let mut layer = Map::new();
+
+    let mut names: Vec<(String, &str)> = FIELDS
+        .iter()
+        .map(|(field, _)| (env_var_name(field), *field))
+        .collect();
+    names.extend(
+        ENV_ALIASES
+            .iter()
+            .map(|(alias, field)| (format!("{}{}", ENV_PREFIX, alias), *field)),
+    );
+
+    for (var, field) in names {
+        let Ok(raw) = std::env::var(&var) else {
+            continue;
+        };
+        let kind = field_kind(field).unwrap_or(FieldKind::String);
+        let value = parse_field_value(kind, &raw)
+            .map_err(|e| anyhow!("Invalid value for {}: {}", var, e))?;
+        layer.insert(field.to_string(), value);
+    }
+
+    Ok(layer)
+}
+
+/// `GITHUB_TOKEN`/`GH_TOKEN` as a fallback API key.
+fn token_env_layer() -> Map<String, Value> {
+    let mut layer = Map::new();
+    if let Some(token) = TOKEN_ENV_VARS
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.trim().is_empty())
+    {
+        layer.insert(
+            "api_key".to_string(),
+            Value::String(token.trim().to_string()),
+        );
+    }
+    layer
+}
+
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    pub config: Config,
+    /// Layer that supplied each field of `config`.
+    pub sources: BTreeMap<String, ConfigSource>,
+    /// Config files that were found and applied, lowest precedence first.
+    pub files: Vec<(ConfigSource, PathBuf)>,
+}
+
+pub fn get_repo_config_file(repo_root: &str) -> Option<PathBuf> {
+    REPO_CONFIG_FILES
+        .iter()
+        .map(|name| Path::new(repo_root).join(name))
+        .find(|path| path.exists())
+}
+
+pub fn get_local_config_file(repo_root: &str) -> Result<PathBuf> {
+    crate::git::get_git_path(repo_root, LOCAL_CONFIG_FILE)
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("toml")
+}
+
+/// Reads a config layer as a JSON object. TOML files are converted so that
+/// all layers merge the same way.
+fn read_layer(path: &Path) -> Result<Map<String, Value>> {
+    parse_layer(path, &fs::read_to_string(path)?)
+}
+
+fn parse_layer(path: &Path, content: &str) -> Result<Map<String, Value>> {
+    let value: Value = if is_toml(path) {
+        let parsed: toml::Value = toml::from_str(content)
+            .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))?;
+        serde_json::to_value(parsed)?
+    } else {
+        serde_json::from_str(content).map_err(|e| json_parse_error(path, &e))?
+    };
+
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => Err(anyhow!(
+            "{} must contain an object/table of settings",
+            path.display()
+        )),
+    }
+}
+
+/// Current config schema version. Files without a `version` field are
+/// version 1.
+pub const CONFIG_VERSION: u64 = 2;
+
+/// `MIGRATIONS[n]` upgrades a version `n + 1` file to version `n + 2`.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[migrate_v1_to_v2];
+
+/// Version 1 files were written by serializing the whole struct, so every
+/// unset option was stored as `null`. Dropping them lets lower layers and
+/// defaults apply.
+fn migrate_v1_to_v2(layer: &mut Map<String, Value>) {
+    layer.retain(|_, value| !value.is_null());
+}
+
+pub fn layer_version(layer: &Map<String, Value>) -> u64 {
+    layer.get("version").and_then(|v| v.as_u64()).unwrap_or(1)
+}
+
+/// Upgrades a layer read from `path` to `CONFIG_VERSION` in memory. The
+/// file itself is upgraded the next time it is written.
+pub fn migrate_layer(layer: &mut Map<String, Value>, path: &Path) -> Result<()> {
+    let version = layer_version(layer);
+    if version > CONFIG_VERSION {
+        return Err(anyhow!(
+            "{} has config version {}, but this autocommiter only understands up to version {}; upgrade autocommiter",
+            path.display(),
+            version,
+            CONFIG_VERSION
+        ));
+    }
+    for migration in MIGRATIONS.iter().skip(version.saturating_sub(1) as usize) {
+        migration(layer);
+    }
+    layer.insert("version".to_string(), Value::from(CONFIG_VERSION));
     Ok(())
 }
 
-pub fn get_api_key() -> Result<Option<String>> {
-    let config = load_config()?;
-    Ok(config.api_key)
+/// Keys that are not config fields, including `profiles.<name>.<field>`.
+pub fn unknown_fields(layer: &Map<String, Value>) -> Vec<String> {
+    let mut unknown: Vec<String> = layer
+        .keys()
+        .filter(|key| field_kind(key).is_none())
+        .cloned()
+        .collect();
+    if let Some(Value::Object(profiles)) = layer.get("profiles") {
+        for (name, settings) in profiles {
+            if let Value::Object(settings) = settings {
+                unknown.extend(
+                    settings
+                        .keys()
+                        .filter(|key| field_kind(key).is_none())
+                        .map(|key| format!("profiles.{}.{}", name, key)),
+                );
+            }
+        }
+    }
+    unknown
 }
 
-pub fn set_api_key(key: String) -> Result<()> {
-    let mut config = load_config()?;
-    config.api_key = Some(key);
-    save_config(&config)
+/// Deserializes the raw text straight into `Config`, so that a value of the
+/// wrong type is reported with its line and column.
+fn check_field_types(path: &Path, content: &str) -> Result<()> {
+    if is_toml(path) {
+        toml::from_str::<Config>(content)
+            .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))?;
+    } else {
+        serde_json::from_str::<Config>(content).map_err(|e| json_parse_error(path, &e))?;
+    }
+    Ok(())
 }
 
-pub fn get_selected_model() -> Result<String> {
-    let config = load_config()?;
-    Ok(config
-        .selected_model
-        .unwrap_or_else(|| "gpt-4o-mini".to_string()))
+/// Reads, migrates and checks a config file for use as a layer.
+fn load_layer(path: &Path) -> Result<Map<String, Value>> {
+    let content = fs::read_to_string(path)?;
+    let mut layer = parse_layer(path, &content)?;
+    check_field_types(path, &content)?;
+    migrate_layer(&mut layer, path)?;
+    for key in unknown_fields(&layer) {
+        tracing::warn!(
+            "Unknown config field '{}' in {}; it is ignored (run `autocommiter config validate`)",
+            key,
+            path.display()
+        );
+    }
+    Ok(layer)
 }
 
-pub fn set_selected_model(model: String) -> Result<()> {
-    let mut config = load_config()?;
-    config.selected_model = Some(model);
-    save_config(&config)
+/// Table fields merged entry by entry across layers instead of replaced.
+const MERGED_TABLES: &[&str] = &["model_aliases"];
+
+fn merge_layer(
+    merged: &mut Map<String, Value>,
+    sources: &mut BTreeMap<String, ConfigSource>,
+    layer: Map<String, Value>,
+    source: ConfigSource,
+) {
+    for (key, value) in layer {
+        if value.is_null() {
+            continue;
+        }
+        sources.insert(key.clone(), source);
+        match (merged.get_mut(&key), value) {
+            (Some(Value::Object(existing)), Value::Object(entries))
+                if MERGED_TABLES.contains(&key.as_str()) =>
+            {
+                existing.extend(entries);
+            }
+            (_, value) => {
+                merged.insert(key, value);
+            }
+        }
+    }
 }
 
-#[allow(dead_code)]
-pub fn is_gitmoji_enabled() -> Result<bool> {
-    let config = load_config()?;
-    Ok(config.enable_gitmoji.unwrap_or(false))
+/// The repo file's value for a `TIGHTEN_ONLY_FIELDS` entry, given the value
+/// from the layers below it, or `None` when it would loosen that value.
+/// `gitignore_patterns` and `redact_patterns` are added to the inherited
+/// ones.
+fn tightened_value(field: &str, current: Option<&Value>, value: Value) -> Option<Value> {
+    if current == Some(&value) {
+        return Some(value);
+    }
+    let policy_rank = |v: &Value| match v.as_str() {
+        Some("warn") => Some(0),
+        Some("prompt") => Some(1),
+        Some("abort") => Some(2),
+        _ => None,
+    };
+    let accepted = match field {
+        "gitignore_patterns" | "redact_patterns" => {
+            let mut patterns = current
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default();
+            let Value::Array(added) = value else {
+                return Some(value);
+            };
+            for pattern in added {
+                if !patterns.contains(&pattern) {
+                    patterns.push(pattern);
+                }
+            }
+            return Some(Value::Array(patterns));
+        }
+        "secret_allowlist" | "tracked_ignored_allowlist" => {
+            value.as_array().is_some_and(|a| a.is_empty())
+        }
+        "privacy_mode" => value == "exclude",
+        "tracked_ignored_policy" => match (current.and_then(policy_rank), policy_rank(&value)) {
+            (Some(current), Some(new)) => new >= current,
+            (None, new) => new.is_some(),
+            (_, None) => false,
+        },
+        "max_file_size_kb" => match (current.and_then(|v| v.as_u64()), value.as_u64()) {
+            (Some(current), Some(new)) => new <= current,
+            _ => false,
+        },
+        _ => value == Value::Bool(false),
+    };
+    accepted.then_some(value)
+}
+
+/// Drops repo-file settings that would loosen `TIGHTEN_ONLY_FIELDS` relative
+/// to `below` (the layers the repo file overrides).
+fn restrict_repo_layer(repo: &mut Map<String, Value>, below: &Map<String, Value>, path: &Path) {
+    // A `!pattern` would re-include paths the inherited patterns cover
+    if let Some(Value::Array(patterns)) = repo.get_mut("gitignore_patterns") {
+        patterns.retain(|pattern| {
+            let negated = pattern.as_str().is_some_and(|p| p.starts_with('!'));
+            if negated {
+                tracing::warn!(
+                    "Ignoring gitignore_patterns entry {} in {}; the repository file may only add patterns",
+                    pattern,
+                    path.display()
+                );
+            }
+            !negated
+        });
+    }
+    for field in TIGHTEN_ONLY_FIELDS {
+        let Some(value) = repo.remove(*field).filter(|v| !v.is_null()) else {
+            continue;
+        };
+        match tightened_value(field, below.get(*field), value.clone()) {
+            Some(value) => {
+                repo.insert(field.to_string(), value);
+            }
+            None => tracing::warn!(
+                "Ignoring {} = {} in {}; the repository file may only tighten it. Set it globally or in the local override instead",
+                field,
+                value,
+                path.display()
+            ),
+        }
+    }
+}
+
+/// True for model specs that name a provider other than GitHub Models, such
+/// as `openai:gpt-4o`. Invalid specs are left for validation to report.
+fn uses_other_provider(spec: &Value) -> bool {
+    spec.as_str()
+        .and_then(|spec| crate::api_client::ModelRef::parse(spec).ok())
+        .is_some_and(|model| model.provider != crate::api_client::Provider::Github)
+}
+
+/// Drops model entries in the repo file that point at another provider: they
+/// would spend the user's own key for that provider (e.g. `OPENAI_API_KEY`)
+/// just because the repository asked for it.
+fn restrict_repo_models(repo: &mut Map<String, Value>, path: &Path) {
+    let mut dropped = Vec::new();
+    if repo.get("selected_model").is_some_and(uses_other_provider) {
+        dropped.extend(repo.remove("selected_model"));
+    }
+    if let Some(Value::Array(models)) = repo.get_mut("fallback_models") {
+        models.retain(|spec| {
+            let keep = !uses_other_provider(spec);
+            if !keep {
+                dropped.push(spec.clone());
+            }
+            keep
+        });
+    }
+    if let Some(Value::Object(aliases)) = repo.get_mut("model_aliases") {
+        aliases.retain(|_, spec| {
+            let keep = !uses_other_provider(spec);
+            if !keep {
+                dropped.push(spec.clone());
+            }
+            keep
+        });
+    }
+    for spec in dropped {
+        tracing::warn!(
+            "Ignoring model {} in {}; models on other providers can only be configured globally, in the local override or the environment",
+            spec,
+            path.display()
+        );
+    }
+}
+
+/// Profiles defined in the global and local files; a local profile replaces
+/// a global one of the same name.
+fn collect_profiles(layers: &[&Map<String, Value>]) -> Map<String, Value> {
+    let mut profiles = Map::new();
+    for layer in layers {
+        if let Some(Value::Object(defined)) = layer.get("profiles") {
+            profiles.extend(defined.clone());
+        }
+    }
+    profiles
+}
+
+/// Builds the effective configuration for `repo_root` (or just defaults and
+/// the global file outside a repository), merging field by field. `profile`
+/// is the `--profile` flag, which takes precedence over
+/// `AUTOCOMMITER_PROFILE` and the `profile` setting.
+pub fn load_layered_config(
+    repo_root: Option<&str>,
+    profile: Option<&str>,
+) -> Result<LayeredConfig> {
+    let mut files = Vec::new();
+
+    let mut global = Map::new();
+    let global_file = get_config_file()?;
+    if global_file.exists() {
+        if let Value::Object(map) = serde_json::to_value(load_config()?)? {
+            global = map;
+        }
+        files.push((ConfigSource::Global, global_file));
+    }
+
+    let mut repo = Map::new();
+    let mut repo_path = None;
+    let mut local = Map::new();
+    if let Some(root) = repo_root {
+        if let Some(repo_file) = get_repo_config_file(root) {
+            repo = load_layer(&repo_file)?;
+            for field in REPO_IGNORED_FIELDS {
+                if repo.remove(*field).is_some() {
+                    tracing::warn!(
+                        "Ignoring {} in {}; set it globally or in the local override instead",
+                        field,
+                        repo_file.display()
+                    );
+                }
+            }
+            restrict_repo_models(&mut repo, &repo_file);
+            if repo.remove("profiles").is_some() {
+                tracing::warn!(
+                    "Ignoring profiles in {}; define profiles globally and select one with `profile`",
+                    repo_file.display()
+                );
+            }
+            repo_path = Some(repo_file.clone());
+            files.push((ConfigSource::Repo, repo_file));
+        }
+
+        let local_file = get_local_config_file(root)?;
+        if local_file.exists() {
+            local = load_layer(&local_file)?;
+            files.push((ConfigSource::Local, local_file));
+        }
+    }
+
+    let env = env_layer()?;
+    let profiles = collect_profiles(&[&global, &local]);
+
+    // A name given explicitly (flag or environment) must exist; one read
+    // from a config file only warns, so a stale `profile` setting does not
+    // break every command.
+    let explicit_profile = profile.map(str::to_string).or_else(|| {
+        env.get("profile")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    });
+    let from_file = explicit_profile.is_none();
+    let mut profile_name = explicit_profile.or_else(|| {
+        [&local, &repo, &global]
+            .iter()
+            .find_map(|layer| layer.get("profile").and_then(|v| v.as_str()))
+            .map(|name| name.to_string())
+    });
+    let mut profile_layer = Map::new();
+    if let Some(name) = profile_name.clone() {
+        let defined = if profiles.is_empty() {
+            "none".to_string()
+        } else {
+            profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+        };
+        match profiles.get(&name) {
+            Some(Value::Object(settings)) => profile_layer = settings.clone(),
+            Some(_) => return Err(anyhow!("Profile '{}' must be an object of settings", name)),
+            None if from_file => {
+                tracing::warn!(
+                    "Unknown profile '{}' set in the config; continuing without a profile. Defined profiles: {}",
+                    name,
+                    defined
+                );
+                profile_name = None;
+            }
+            None => {
+                return Err(anyhow!(
+                    "Unknown profile '{}'. Defined profiles: {}",
+                    name,
+                    defined
+                ))
+            }
+        }
+        profile_layer.remove("profile");
+        profile_layer.remove("profiles");
+    }
+
+    let mut merged = Map::new();
+    let mut sources = BTreeMap::new();
+    if let Value::Object(defaults) = serde_json::to_value(Config::default())? {
+        merge_layer(&mut merged, &mut sources, defaults, ConfigSource::Default);
+    }
+    let below_repo = [
+        (token_env_layer(), ConfigSource::TokenEnv),
+        (global, ConfigSource::Global),
+        (profile_layer, ConfigSource::Profile),
+    ];
+    for (layer, source) in below_repo {
+        merge_layer(&mut merged, &mut sources, layer, source);
+    }
+    if let Some(path) = &repo_path {
+        restrict_repo_layer(&mut repo, &merged, path);
+    }
+    let above_repo = [
+        (repo, ConfigSource::Repo),
+        (local, ConfigSource::Local),
+        (env, ConfigSource::Env),
+    ];
+    for (layer, source) in above_repo {
+        merge_layer(&mut merged, &mut sources, layer, source);
+    }
+    if !profiles.is_empty() {
+        merged.insert("profiles".to_string(), Value::Object(profiles));
+    }
+    match &profile_name {
+        Some(name) => merged.insert("profile".to_string(), Value::String(name.clone())),
+        None => merged.remove("profile"),
+    };
+
+    let config: Config = serde_json::from_value(Value::Object(merged))
+        .map_err(|e| anyhow!("Invalid configuration value: {}", e))?;
+
+    Ok(LayeredConfig {
+        config,
+        sources,
+        files,
+    })
+}
+
+pub fn load_effective_config(repo_root: &str, profile: Option<&str>) -> Result<Config> {
+    Ok(load_layered_config(Some(repo_root), profile)?.config)
+}
+
+/// Effective configuration for the current directory's repository, if any.
+pub fn load_current_config(profile: Option<&str>) -> Result<Config> {
+    let repo_root = crate::git::get_repo_root(".").ok();
+    Ok(load_layered_config(repo_root.as_deref(), profile)?.config)
+}
+
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Replaces the global config with one that sets nothing, so the defaults
+/// apply. The old file is moved to its `.bak` path without being parsed, so
+/// a broken file can be reset too. Returns the backup path when there was a
+/// file to replace.
+pub fn reset_config() -> Result<Option<PathBuf>> {
+    let path = get_config_file()?;
+    let backup = if path.exists() {
+        let backup = backup_path(&path);
+        fs::rename(&path, &backup)?;
+        Some(backup)
+    } else {
+        None
+    };
+    update_config_file(&path, |_| Ok(()))?;
+    Ok(backup)
+}
+
+/// One problem found by `config validate`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationIssue {
+    /// `error` or `warning`.
+    pub level: &'static str,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub fn error(field: Option<String>, message: String) -> Self {
+        Self {
+            level: "error",
+            field,
+            message,
+        }
+    }
+
+    pub fn warning(field: Option<String>, message: String) -> Self {
+        Self {
+            level: "warning",
+            field,
+            message,
+        }
+    }
+}
+
+/// String fields that only accept a fixed set of values.
+const ALLOWED_VALUES: &[(&str, &[&str])] = &[
+    ("privacy_mode", &["placeholder", "exclude"]),
+    (
+        "tracked_ignored_policy",
+        &["prompt", "warn", "abort", "untrack"],
+    ),
+];
+
+/// Regex list fields.
+const REGEX_FIELDS: &[&str] = &["secret_allowlist", "redact_patterns"];
+
+/// Fields holding `provider:model` references.
+const MODEL_FIELDS: &[&str] = &["selected_model", "fallback_models", "model_aliases"];
+
+fn check_settings(settings: &Map<String, Value>, prefix: &str, issues: &mut Vec<ValidationIssue>) {
+    for (key, value) in settings {
+        let name = format!("{}{}", prefix, key);
+        if value.is_null() {
+            continue;
+        }
+        if field_kind(key).is_none() {
+            issues.push(ValidationIssue::warning(
+                Some(name),
+                "unknown field; it is ignored".to_string(),
+            ));
+            continue;
+        }
+        if !prefix.is_empty() && matches!(key.as_str(), "profile" | "profiles" | "version") {
+            issues.push(ValidationIssue::warning(
+                Some(name),
+                "has no effect inside a profile".to_string(),
+            ));
+            continue;
+        }
+
+        // Deserializing the field on its own gives a precise type error
+        let mut single = Map::new();
+        single.insert(key.clone(), value.clone());
+        if let Err(e) = serde_json::from_value::<Config>(Value::Object(single)) {
+            issues.push(ValidationIssue::error(Some(name), e.to_string()));
+            continue;
+        }
+
+        if let Some((_, allowed)) = ALLOWED_VALUES.iter().find(|(field, _)| field == key) {
+            let value = value.as_str().unwrap_or_default();
+            if !allowed.contains(&value) {
+                issues.push(ValidationIssue::error(
+                    Some(name.clone()),
+                    format!("'{}' is not one of: {}", value, allowed.join(", ")),
+                ));
+            }
+        }
+        if REGEX_FIELDS.contains(&key.as_str()) {
+            for pattern in value
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|p| p.as_str())
+            {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    issues.push(ValidationIssue::error(
+                        Some(name.clone()),
+                        format!("invalid regex '{}': {}", pattern, e),
+                    ));
+                }
+            }
+        }
+        if MODEL_FIELDS.contains(&key.as_str()) {
+            let specs = match value {
+                Value::Array(items) => items.iter().filter_map(|v| v.as_str()).collect(),
+                Value::Object(aliases) => aliases.values().filter_map(|v| v.as_str()).collect(),
+                value => value.as_str().into_iter().collect::<Vec<_>>(),
+            };
+            for spec in specs {
+                if let Err(e) = crate::api_client::ModelRef::parse(spec) {
+                    issues.push(ValidationIssue::error(Some(name.clone()), e.to_string()));
+                }
+            }
+        }
+        if key == "model_aliases" {
+            for alias in value
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(alias, _)| alias)
+            {
+                if alias.contains(':') {
+                    issues.push(ValidationIssue::error(
+                        Some(name.clone()),
+                        format!("alias '{}' must not contain ':'", alias),
+                    ));
+                }
+            }
+        }
+        if key == "profiles" {
+            for (profile, settings) in value.as_object().into_iter().flatten() {
+                if let Value::Object(settings) = settings {
+                    check_settings(settings, &format!("profiles.{}.", profile), issues);
+                }
+            }
+        }
+    }
+}
+
+/// Checks a config file against the schema without applying it.
+pub fn validate_file(path: &Path) -> Vec<ValidationIssue> {
+    let layer = match read_layer(path) {
+        Ok(layer) => layer,
+        Err(e) => return vec![ValidationIssue::error(None, e.to_string())],
+    };
+
+    let mut issues = Vec::new();
+    match layer.get("version") {
+        None => issues.push(ValidationIssue::warning(
+            Some("version".to_string()),
+            format!(
+                "missing; read as version 1 and migrated to version {}",
+                CONFIG_VERSION
+            ),
+        )),
+        Some(v) if v.as_u64().is_some_and(|v| v > CONFIG_VERSION) => {
+            issues.push(ValidationIssue::error(
+                Some("version".to_string()),
+                format!(
+                    "{} is newer than the supported version {}",
+                    v, CONFIG_VERSION
+                ),
+            ))
+        }
+        _ => {}
+    }
+    check_settings(&layer, "", &mut issues);
+    issues
+}
+
+/// Config file written by `config set/unset/add/remove`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigScope {
+    Global,
+    Local,
+}
+
+impl fmt::Display for ConfigScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigScope::Global => write!(f, "global"),
+            ConfigScope::Local => write!(f, "local"),
+        }
+    }
+}
+
+pub fn get_scope_file(scope: ConfigScope, cwd: &str) -> Result<PathBuf> {
+    match scope {
+        ConfigScope::Global => get_config_file(),
+        ConfigScope::Local => {
+            let root = crate::git::get_repo_root(cwd)
+                .map_err(|_| anyhow!("--local needs to be run inside a git repository"))?;
+            get_local_config_file(&root)
+        }
+    }
+}
+
+/// Looks up a field by name, listing the valid names when it is unknown.
+pub fn require_field(key: &str) -> Result<FieldKind> {
+    field_kind(key).ok_or_else(|| {
+        anyhow!(
+            "Unknown config key '{}'. Known keys: {}",
+            key,
+            FIELDS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    })
+}
+
+/// Settings stored in a single config file, without defaults or other
+/// layers. A missing file is empty.
+pub fn read_config_file(path: &Path) -> Result<Map<String, Value>> {
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+    let mut map = read_layer(path)?;
+    migrate_layer(&mut map, path)?;
+    Ok(map)
+}
+
+/// Applies `edit` to the settings in `path` and writes the result back,
+/// keeping the previous file as `<name>.bak`. Only explicitly set fields and
+/// the current `version` are stored, so lower layers and the defaults keep
+/// supplying everything else.
+/// An existing file that does not parse is never replaced, so a typo cannot
+/// wipe the API key and other settings.
+pub fn update_config_file(
+    path: &Path,
+    edit: impl FnOnce(&mut Map<String, Value>) -> Result<()>,
+) -> Result<()> {
+    let mut map = read_config_file(path)?;
+    edit(&mut map)?;
+    map.insert("version".to_string(), Value::from(CONFIG_VERSION));
+    serde_json::from_value::<Config>(Value::Object(map.clone()))
+        .map_err(|e| anyhow!("Invalid configuration value: {}", e))?;
+
+    if path.exists() {
+        fs::copy(path, backup_path(path))?;
+    } else if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(&map)?)?;
+    Ok(())
+}
+
+/// Where `select-model` and the toggles store `field`: under
+/// `profiles.<name>` in the file that defines `profile` (the local override
+/// wins, as it does when profiles are collected), or at the top level of the
+/// global file without a profile. Returns the file and the key.
+pub fn field_target(
+    repo_root: Option<&str>,
+    profile: Option<&str>,
+    field: &str,
+) -> Result<(PathBuf, String)> {
+    let Some(name) = profile else {
+        return Ok((get_config_file()?, field.to_string()));
+    };
+    let mut files = Vec::new();
+    if let Some(root) = repo_root {
+        files.push(get_local_config_file(root)?);
+    }
+    files.push(get_config_file()?);
+    for path in files {
+        let defined = read_config_file(&path)?
+            .get("profiles")
+            .and_then(|profiles| profiles.get(name))
+            .is_some();
+        if defined {
+            return Ok((path, format!("profiles.{}.{}", name, field)));
+        }
+    }
+    Err(anyhow!("Unknown profile '{}'", name))
+}
+
+/// Stores `value` for `field` at its `field_target`. Returns the file and
+/// the key written.
+pub fn set_field(
+    repo_root: Option<&str>,
+    profile: Option<&str>,
+    field: &str,
+    value: Value,
+) -> Result<(PathBuf, String)> {
+    let (path, key) = field_target(repo_root, profile, field)?;
+    update_config_file(&path, |map| {
+        set_key(map, &key, value);
+        Ok(())
+    })?;
+    Ok((path, key))
 }
 
 #[allow(dead_code)]
-pub fn set_gitmoji_enabled(enabled: bool) -> Result<()> {
-    let mut config = load_config()?;
-    config.enable_gitmoji = Some(enabled);
-    save_config(&config)
+pub fn is_gitmoji_enabled() -> Result<bool> {
+    let config = load_current_config(None)?;
+    Ok(config.enable_gitmoji.unwrap_or(false))
 }
 
 #[allow(dead_code)]
 pub fn get_gitignore_patterns() -> Result<Vec<String>> {
-    let config = load_config()?;
+    let config = load_current_config(None)?;
     Ok(config.gitignore_patterns.unwrap_or_else(|| {
         vec![
             "*.env*".to_string(),
             ".env*".to_string(),
+            "!.env.example".to_string(),
+            "!.env.sample".to_string(),
             "docx/".to_string(),
             ".docx/".to_string(),
         ]
@@ -107,13 +1250,329 @@ pub fn get_gitignore_patterns() -> Result<Vec<String>> {
 
 #[allow(dead_code)]
 pub fn is_skip_confirmation_enabled() -> Result<bool> {
-    let config = load_config()?;
+    let config = load_current_config(None)?;
     Ok(config.skip_confirmation.unwrap_or(false))
 }
 
-#[allow(dead_code)]
-pub fn set_skip_confirmation(enabled: bool) -> Result<()> {
-    let mut config = load_config()?;
-    config.skip_confirmation = Some(enabled);
-    save_config(&config)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_toml_layer_overrides_field_by_field() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".autocommiter.toml");
+        fs::write(
+            &path,
+            "selected_model = \"gpt-4o\"\ngitignore_patterns = [\".env\"]\n",
+        )
+        .unwrap();
+
+        let mut merged = Map::new();
+        let mut sources = BTreeMap::new();
+        if let Value::Object(defaults) = serde_json::to_value(Config::default()).unwrap() {
+            merge_layer(&mut merged, &mut sources, defaults, ConfigSource::Default);
+        }
+        merge_layer(
+            &mut merged,
+            &mut sources,
+            read_layer(&path).unwrap(),
+            ConfigSource::Repo,
+        );
+
+        let config: Config = serde_json::from_value(Value::Object(merged)).unwrap();
+        assert_eq!(config.selected_model.as_deref(), Some("gpt-4o"));
+        assert_eq!(config.gitignore_patterns, Some(vec![".env".to_string()]));
+        assert_eq!(config.enable_gitmoji, Some(false));
+        assert_eq!(sources["selected_model"], ConfigSource::Repo);
+        assert_eq!(sources["enable_gitmoji"], ConfigSource::Default);
+    }
+
+    #[test]
+    fn test_repo_layer_may_only_tighten_safety_fields() {
+        let below = serde_json::to_value(Config {
+            redact_patterns: Some(vec!["internal-\\d+".to_string()]),
+            ..Config::default()
+        })
+        .unwrap();
+        let Value::Object(below) = below else {
+            panic!("config must serialize to an object");
+        };
+        let mut repo: Map<String, Value> = serde_json::from_str(
+            r#"{
+                "gitignore_patterns": ["*.pem", "!.env"],
+                "secret_allowlist": [".*"],
+                "redact_patterns": ["acme-[a-z]+"],
+                "privacy_mode": "exclude",
+                "tracked_ignored_policy": "untrack",
+                "tracked_ignored_allowlist": [".env"],
+                "max_file_size_kb": 100000,
+                "allow_large_files": true,
+                "allow_binary_files": false,
+                "enable_gitmoji": true
+            }"#,
+        )
+        .unwrap();
+        restrict_repo_layer(&mut repo, &below, Path::new(".autocommiter.json"));
+
+        assert!(!repo.contains_key("secret_allowlist"));
+        assert!(!repo.contains_key("tracked_ignored_policy"));
+        assert!(!repo.contains_key("tracked_ignored_allowlist"));
+        assert!(!repo.contains_key("max_file_size_kb"));
+        assert!(!repo.contains_key("allow_large_files"));
+        assert_eq!(
+            repo["redact_patterns"],
+            serde_json::json!(["internal-\\d+", "acme-[a-z]+"])
+        );
+        let patterns = repo["gitignore_patterns"].as_array().unwrap();
+        assert!(patterns.contains(&Value::from(".env*")));
+        assert_eq!(patterns.last(), Some(&Value::from("*.pem")));
+        assert!(!patterns.contains(&Value::from("!.env")));
+        assert_eq!(repo["privacy_mode"], "exclude");
+        assert_eq!(repo["allow_binary_files"], false);
+        assert_eq!(repo["enable_gitmoji"], true);
+
+        let below: Map<String, Value> = serde_json::from_str(
+            r#"{"tracked_ignored_policy": "warn", "privacy_mode": "exclude"}"#,
+        )
+        .unwrap();
+        let mut repo: Map<String, Value> = serde_json::from_str(
+            r#"{"tracked_ignored_policy": "abort", "privacy_mode": "placeholder"}"#,
+        )
+        .unwrap();
+        restrict_repo_layer(&mut repo, &below, Path::new(".autocommiter.json"));
+        assert_eq!(repo["tracked_ignored_policy"], "abort");
+        assert!(!repo.contains_key("privacy_mode"));
+    }
+
+    #[test]
+    fn test_model_aliases_merge_and_resolve() {
+        let mut merged = Map::new();
+        let mut sources = BTreeMap::new();
+        let layer = |json: &str| serde_json::from_str::<Map<String, Value>>(json).unwrap();
+        merge_layer(
+            &mut merged,
+            &mut sources,
+            layer(r#"{"model_aliases": {"fast": "gpt-4o-mini", "smart": "gpt-4o"}}"#),
+            ConfigSource::Global,
+        );
+        merge_layer(
+            &mut merged,
+            &mut sources,
+            layer(r#"{"model_aliases": {"smart": "openai:gpt-4o"}, "selected_model": "smart"}"#),
+            ConfigSource::Local,
+        );
+
+        let config: Config = serde_json::from_value(Value::Object(merged)).unwrap();
+        assert_eq!(config.effective_model(), "openai:gpt-4o");
+        assert_eq!(config.resolve_model("fast"), "gpt-4o-mini");
+        assert_eq!(config.resolve_model("gpt-4o"), "gpt-4o");
+        assert_eq!(config.aliases_for("gpt-4o-mini"), vec!["fast"]);
+    }
+
+    #[test]
+    fn test_repo_layer_drops_other_providers() {
+        let mut repo: Map<String, Value> = serde_json::from_str(
+            r#"{
+                "selected_model": "openai:gpt-4o",
+                "fallback_models": ["gpt-4o-mini", "openai:gpt-4o-mini"],
+                "model_aliases": {"fast": "gpt-4o-mini", "smart": "openai:gpt-4o"}
+            }"#,
+        )
+        .unwrap();
+        restrict_repo_models(&mut repo, Path::new(".autocommiter.toml"));
+
+        assert!(!repo.contains_key("selected_model"));
+        assert_eq!(repo["fallback_models"], serde_json::json!(["gpt-4o-mini"]));
+        assert_eq!(
+            repo["model_aliases"],
+            serde_json::json!({"fast": "gpt-4o-mini"})
+        );
+    }
+
+    #[test]
+    fn test_fields_cover_config() {
+        let Value::Object(map) = serde_json::to_value(Config::default()).unwrap() else {
+            panic!("config must serialize to an object");
+        };
+        for key in map.keys() {
+            assert!(field_kind(key).is_some(), "{} missing from FIELDS", key);
+        }
+        assert_eq!(map.len(), FIELDS.len());
+    }
+
+    #[test]
+    fn test_parse_field_value() {
+        assert_eq!(
+            parse_field_value(FieldKind::Bool, "Yes").unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            parse_field_value(FieldKind::List, ".env, *.pem,").unwrap(),
+            serde_json::json!([".env", "*.pem"])
+        );
+        assert_eq!(
+            parse_field_value(FieldKind::Integer, "2048").unwrap(),
+            Value::from(2048u64)
+        );
+        assert!(parse_field_value(FieldKind::Bool, "maybe").is_err());
+    }
+
+    #[test]
+    fn test_malformed_config_is_reported_and_never_overwritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(
+            &path,
+            "{\n  \"api_key\": \"abc\"\n  \"enable_gitmoji\": true\n}",
+        )
+        .unwrap();
+
+        let err = load_config_from(&path).unwrap_err().to_string();
+        assert!(err.contains("line 3"), "{}", err);
+
+        let typo = dir.path().join("typo.json");
+        fs::write(
+            &typo,
+            "{\n  \"version\": 2,\n  \"enable_gitmoji\": \"yes\"\n}",
+        )
+        .unwrap();
+        let err = load_config_from(&typo).unwrap_err().to_string();
+        assert!(err.contains("line 3"), "{}", err);
+
+        assert!(update_config_file(&path, |_| Ok(())).is_err());
+        assert!(fs::read_to_string(&path).unwrap().contains("\"abc\"\n"));
+        assert!(!backup_path(&path).exists());
+    }
+
+    #[test]
+    fn test_save_keeps_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+
+        let set_model = |model: &str| {
+            update_config_file(&path, |map| {
+                map.insert("selected_model".to_string(), Value::from(model));
+                Ok(())
+            })
+        };
+        set_model("gpt-4o").unwrap();
+        assert!(!backup_path(&path).exists());
+
+        set_model("Mistral-large").unwrap();
+
+        let backup = load_config_from(&backup_path(&path)).unwrap();
+        assert_eq!(backup.selected_model.as_deref(), Some("gpt-4o"));
+        let current = load_config_from(&path).unwrap();
+        assert_eq!(current.selected_model.as_deref(), Some("Mistral-large"));
+    }
+
+    #[test]
+    fn test_update_config_file_stores_only_set_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("autocommiter.json");
+
+        update_config_file(&path, |map| {
+            map.insert("enable_gitmoji".to_string(), Value::Bool(true));
+            Ok(())
+        })
+        .unwrap();
+        let stored = read_config_file(&path).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored["enable_gitmoji"], Value::Bool(true));
+        // Written to the file itself, not just added when it is read
+        assert_eq!(
+            read_layer(&path).unwrap()["version"],
+            Value::from(CONFIG_VERSION)
+        );
+
+        let err = update_config_file(&path, |map| {
+            map.insert("max_file_size_kb".to_string(), Value::from("big"));
+            Ok(())
+        });
+        assert!(err.is_err());
+        assert_eq!(read_config_file(&path).unwrap(), stored);
+    }
+
+    #[test]
+    fn test_global_list_starts_from_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, r#"{"version": 2, "enable_gitmoji": true}"#).unwrap();
+
+        // What `config add gitignore_patterns '*.pem'` stores when the
+        // global file does not set the list yet
+        let global = load_global_config_from(&path).unwrap();
+        assert_eq!(global.enable_gitmoji, Some(true));
+        let mut patterns = global.gitignore_patterns.unwrap();
+        patterns.push("*.pem".to_string());
+        update_config_file(&path, |map| {
+            map.insert("gitignore_patterns".to_string(), Value::from(patterns));
+            Ok(())
+        })
+        .unwrap();
+
+        let stored = load_config_from(&path).unwrap().gitignore_patterns.unwrap();
+        assert!(stored.contains(&"*.env*".to_string()));
+        assert!(stored.contains(&"!.env.example".to_string()));
+        assert_eq!(stored.last().map(String::as_str), Some("*.pem"));
+    }
+
+    #[test]
+    fn test_profile_keys() {
+        assert_eq!(
+            split_profile_key("profiles.work.selected_model"),
+            Some(("work", "selected_model"))
+        );
+        assert_eq!(split_profile_key("selected_model"), None);
+        assert_eq!(
+            require_key("profiles.work.gitignore_patterns").unwrap(),
+            FieldKind::List
+        );
+        assert!(require_key("profiles.work.profile").is_err());
+        assert!(require_key("version").is_ok());
+        assert!(require_writable_key("version").is_err());
+        assert!(require_writable_key("profiles.work.version").is_err());
+
+        let mut map = Map::new();
+        set_key(&mut map, "profiles.work.enable_gitmoji", Value::Bool(true));
+        assert_eq!(
+            get_key(&map, "profiles.work.enable_gitmoji"),
+            Some(&Value::Bool(true))
+        );
+        remove_key(&mut map, "profiles.work.enable_gitmoji");
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_migrates_v1_and_rejects_newer_versions() {
+        let path = Path::new("config.json");
+        let mut layer = Map::new();
+        layer.insert("api_key".to_string(), Value::Null);
+        layer.insert("enable_gitmoji".to_string(), Value::Bool(true));
+        migrate_layer(&mut layer, path).unwrap();
+        assert!(!layer.contains_key("api_key"));
+        assert_eq!(layer["version"], Value::from(CONFIG_VERSION));
+
+        layer.insert("version".to_string(), Value::from(CONFIG_VERSION + 1));
+        assert!(migrate_layer(&mut layer, path).is_err());
+    }
+
+    #[test]
+    fn test_validate_file_reports_problems() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(
+            &path,
+            r#"{"version": 2, "colour": true, "privacy_mode": "hide", "profiles": {"w": {"enable_gitmoji": "yes"}}}"#,
+        )
+        .unwrap();
+
+        let issues = validate_file(&path);
+        let find = |field: &str| issues.iter().find(|i| i.field.as_deref() == Some(field));
+        assert_eq!(find("colour").unwrap().level, "warning");
+        assert_eq!(find("privacy_mode").unwrap().level, "error");
+        assert_eq!(find("profiles.w.enable_gitmoji").unwrap().level, "error");
+        assert_eq!(issues.len(), 3);
+    }
 }
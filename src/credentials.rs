@@ -0,0 +1,275 @@
+use crate::config::{Config, ConfigSource, LayeredConfig};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Key file name inside the config directory, used when `api_key_file` is
+/// not set.
+pub const KEY_FILE: &str = "api_key";
+
+/// Host asked for when `use_git_credential` is enabled. A GitHub token
+/// stored for github.com also works for GitHub Models.
+pub const GIT_CREDENTIAL_HOST: &str = "github.com";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// `api_key` from a config file or `AUTOCOMMITER_API_KEY`.
+    Config,
+    Command,
+    File,
+    GitCredential,
+    /// `GITHUB_TOKEN`/`GH_TOKEN`.
+    TokenEnv,
+}
+
+impl fmt::Display for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            KeySource::Config => "config",
+            KeySource::Command => "api_key_command",
+            KeySource::File => "key file",
+            KeySource::GitCredential => "git credential helper",
+            KeySource::TokenEnv => "token env",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    pub key: String,
+    pub source: KeySource,
+}
+
+/// `api_key_file` (with `~/` expanded) or `api_key` in the config directory.
+pub fn key_file(config: &Config) -> Result<PathBuf> {
+    match config.api_key_file.as_deref() {
+        Some(path) => match path.strip_prefix("~/") {
+            Some(rest) => Ok(crate::paths::home_dir()?.join(rest)),
+            None => Ok(PathBuf::from(path)),
+        },
+        None => Ok(crate::paths::config_dir()?.join(KEY_FILE)),
+    }
+}
+
+/// Reads a key file, refusing files that other users could read.
+pub fn read_key_file(path: &Path) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path)?.permissions().mode() & 0o777;
+        if mode & 0o077 != 0 {
+            return Err(anyhow!(
+                "API key file {} has permissions {:o}; run `chmod 600 {}`",
+                path.display(),
+                mode,
+                path.display()
+            ));
+        }
+    }
+
+    let key = fs::read_to_string(path)?.trim().to_string();
+    if key.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(key))
+}
+
+/// Writes `key` to a file only the current user can read.
+pub fn write_key_file(path: &Path, key: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // `mode` only applies to new files
+        if path.exists() {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    let mut file = options.open(path)?;
+    writeln!(file, "{}", key)?;
+    Ok(())
+}
+
+/// Runs `api_key_command` through the shell and returns its trimmed stdout.
+pub fn run_key_command(command: &str) -> Result<String> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| anyhow!("Failed to run api_key_command: {}", e))?;
+
+    if !output.status.success() {
+        return Err(anyhow!("api_key_command exited with {}", output.status));
+    }
+    let key = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if key.is_empty() {
+        return Err(anyhow!("api_key_command printed nothing"));
+    }
+    Ok(key)
+}
+
+fn parse_credential_output(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("password="))
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+}
+
+/// Asks git's configured credential helper for a stored token. Never
+/// prompts; returns `None` when no helper has one.
+pub fn git_credential_fill(host: &str) -> Option<String> {
+    let mut child = Command::new("git")
+        .args(["credential", "fill"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GCM_INTERACTIVE", "never")
+        .env_remove("GIT_ASKPASS")
+        .env_remove("SSH_ASKPASS")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    child
+        .stdin
+        .take()?
+        .write_all(format!("protocol=https\nhost={}\n\n", host).as_bytes())
+        .ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_credential_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Finds the API key, in order: `api_key` set in a config file or
+/// `AUTOCOMMITER_API_KEY`, `api_key_command`, the key file, git's
+/// credential helper (when `use_git_credential` is on), and finally
+/// `GITHUB_TOKEN`/`GH_TOKEN`.
+pub fn resolve_api_key(layered: &LayeredConfig) -> Result<Option<ApiKey>> {
+    let config = &layered.config;
+    let from_token_env = layered.sources.get("api_key") == Some(&ConfigSource::TokenEnv);
+
+    if let (Some(key), false) = (&config.api_key, from_token_env) {
+        return Ok(Some(ApiKey {
+            key: key.clone(),
+            source: KeySource::Config,
+        }));
+    }
+
+    if let Some(command) = config.api_key_command.as_deref() {
+        return Ok(Some(ApiKey {
+            key: run_key_command(command)?,
+            source: KeySource::Command,
+        }));
+    }
+
+    if let Some(key) = read_key_file(&key_file(config)?)? {
+        return Ok(Some(ApiKey {
+            key,
+            source: KeySource::File,
+        }));
+    }
+
+    if config.use_git_credential.unwrap_or(false) {
+        if let Some(key) = git_credential_fill(GIT_CREDENTIAL_HOST) {
+            return Ok(Some(ApiKey {
+                key,
+                source: KeySource::GitCredential,
+            }));
+        }
+    }
+
+    Ok(config.api_key.clone().map(|key| ApiKey {
+        key,
+        source: KeySource::TokenEnv,
+    }))
+}
+
+/// Key for `provider`: the resolved GitHub key for GitHub Models, or
+/// `OPENAI_API_KEY` for OpenAI.
+pub fn provider_api_key(
+    provider: crate::api_client::Provider,
+    github_key: Option<&str>,
+) -> Option<String> {
+    match provider {
+        crate::api_client::Provider::Github => github_key.map(str::to_string),
+        crate::api_client::Provider::Openai => std::env::var(crate::api_client::OPENAI_KEY_ENV)
+            .ok()
+            .filter(|key| !key.trim().is_empty()),
+    }
+}
+
+pub fn get_api_key(repo_root: Option<&str>, profile: Option<&str>) -> Result<Option<ApiKey>> {
+    resolve_api_key(&crate::config::load_layered_config(repo_root, profile)?)
+}
+
+/// Saves `key` to the key file in effect (`api_key_file` from any layer,
+/// including the active profile) and drops any plaintext `api_key` left in
+/// the global config. Returns the key file path.
+pub fn store_api_key(key: &str, repo_root: Option<&str>, profile: Option<&str>) -> Result<PathBuf> {
+    let layered = crate::config::load_layered_config(repo_root, profile)?;
+    let path = key_file(&layered.config)?;
+    write_key_file(&path, key)?;
+
+    let global = crate::config::get_config_file()?;
+    if crate::config::read_config_file(&global)?.contains_key("api_key") {
+        crate::config::update_config_file(&global, |map| {
+            map.remove("api_key");
+            Ok(())
+        })?;
+        // The backup would still hold the plaintext key
+        fs::remove_file(crate::config::backup_path(&global))?;
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_credential_output() {
+        let output = "protocol=https\nhost=github.com\nusername=me\npassword=gho_token\n";
+        assert_eq!(
+            parse_credential_output(output).as_deref(),
+            Some("gho_token")
+        );
+        assert_eq!(parse_credential_output("protocol=https\n"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_key_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys/api_key");
+        write_key_file(&path, "secret-key").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+        assert_eq!(read_key_file(&path).unwrap().as_deref(), Some("secret-key"));
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(read_key_file(&path).is_err());
+    }
+}
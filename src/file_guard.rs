@@ -0,0 +1,120 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+pub const DEFAULT_MAX_FILE_SIZE_KB: u64 = 5 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlaggedFile {
+    pub path: String,
+    pub size: u64,
+    pub large: bool,
+    pub binary: bool,
+}
+
+pub struct GuardLimits {
+    pub max_file_size_kb: u64,
+    pub allow_large_files: bool,
+    pub allow_binary_files: bool,
+}
+
+/// Staged files that exceed the size limit or are binary, unless the config
+/// allows that kind of file.
+pub fn find_flagged_files(
+    cwd: &str,
+    staged_files: &[String],
+    limits: &GuardLimits,
+) -> Result<Vec<FlaggedFile>> {
+    let binaries = if limits.allow_binary_files {
+        vec![]
+    } else {
+        crate::git::get_staged_binary_files(cwd)?
+    };
+    let max_bytes = limits.max_file_size_kb.saturating_mul(1024);
+
+    let mut flagged = Vec::new();
+    for path in staged_files {
+        // Deleted files have no staged blob and are never a problem.
+        let Ok(size) = crate::git::get_staged_blob_size(cwd, path) else {
+            continue;
+        };
+        let large = !limits.allow_large_files && size > max_bytes;
+        let binary = binaries.contains(path);
+        if large || binary {
+            flagged.push(FlaggedFile {
+                path: path.clone(),
+                size,
+                large,
+                binary,
+            });
+        }
+    }
+    Ok(flagged)
+}
+
+/// `git lfs track` patterns covering the flagged files: one per extension,
+/// or the exact path for files without one.
+pub fn lfs_track_patterns(files: &[FlaggedFile]) -> Vec<String> {
+    files
+        .iter()
+        .map(|f| {
+            let name = f.path.rsplit('/').next().unwrap_or(&f.path);
+            match name.rsplit_once('.') {
+                Some((stem, ext)) if !stem.is_empty() && !ext.is_empty() => format!("*.{}", ext),
+                _ => f.path.clone(),
+            }
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    let b = bytes as f64;
+    if b >= KB * KB * KB {
+        format!("{:.1} GB", b / (KB * KB * KB))
+    } else if b >= KB * KB {
+        format!("{:.1} MB", b / (KB * KB))
+    } else if b >= KB {
+        format!("{:.1} KB", b / KB)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flagged(path: &str) -> FlaggedFile {
+        FlaggedFile {
+            path: path.to_string(),
+            size: 0,
+            large: true,
+            binary: false,
+        }
+    }
+
+    #[test]
+    fn test_lfs_track_patterns() {
+        let files = vec![
+            flagged("dumps/db.sql"),
+            flagged("assets/a.psd"),
+            flagged("assets/b.psd"),
+            flagged("bin/tool"),
+            flagged(".bashrc"),
+        ];
+        assert_eq!(
+            lfs_track_patterns(&files),
+            vec!["*.psd", "*.sql", ".bashrc", "bin/tool"]
+        );
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(500 * 1024 * 1024), "500.0 MB");
+    }
+}
@@ -1,10 +1,12 @@
 use anyhow::{anyhow, Result};
-use std::process::Command;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
 
 pub fn run_git_command(cmd: &str, cwd: &str) -> Result<String> {
     let output = if cfg!(target_os = "windows") {
         Command::new("cmd")
-            .args(&["/C", cmd])
+            .args(["/C", cmd])
             .current_dir(cwd)
             .output()?
     } else {
@@ -14,7 +16,17 @@ pub fn run_git_command(cmd: &str, cwd: &str) -> Result<String> {
             .current_dir(cwd)
             .output()?
     };
+    command_output(output)
+}
+
+/// Runs git with `args` directly rather than through a shell, for commands
+/// that take file names: `$(...)`, backticks and quotes stay literal.
+fn run_git_args<S: AsRef<OsStr>>(args: &[S], cwd: &str) -> Result<String> {
+    let output = Command::new("git").args(args).current_dir(cwd).output()?;
+    command_output(output)
+}
 
+fn command_output(output: Output) -> Result<String> {
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -44,20 +56,66 @@ pub fn get_staged_files(cwd: &str) -> Result<Vec<String>> {
         .collect())
 }
 
+pub fn get_index_files(cwd: &str) -> Result<Vec<String>> {
+    let output = run_git_command("git ls-files --cached", cwd)?;
+    Ok(output
+        .lines()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect())
+}
+
+pub fn untrack_files(cwd: &str, files: &[String]) -> Result<()> {
+    if files.is_empty() {
+        return Ok(());
+    }
+    let output = Command::new("git")
+        .args(["rm", "--cached", "--quiet", "--"])
+        .args(files)
+        .current_dir(cwd)
+        .output()?;
+    command_output(output)?;
+    Ok(())
+}
+
 pub fn get_staged_diff_numstat(cwd: &str, file: &str) -> Result<String> {
-    let cmd = format!(
-        "git diff --staged --numstat -- \"{}\"",
-        file.replace("\"", "\\\"")
-    );
-    run_git_command(&cmd, cwd)
+    run_git_args(&["diff", "--staged", "--numstat", "--", file], cwd)
 }
 
 pub fn get_staged_diff_unified(cwd: &str, file: &str) -> Result<String> {
-    let cmd = format!(
-        "git diff --staged --unified=0 -- \"{}\"",
-        file.replace("\"", "\\\"")
-    );
-    run_git_command(&cmd, cwd)
+    run_git_args(&["diff", "--staged", "--unified=0", "--", file], cwd)
+}
+
+/// Size in bytes of the staged (index) version of `file`.
+pub fn get_staged_blob_size(cwd: &str, file: &str) -> Result<u64> {
+    let output = run_git_args(&["cat-file", "-s", &format!(":{}", file)], cwd)?;
+    output
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("Unexpected blob size for {}: {}", file, output))
+}
+
+/// Staged files git treats as binary (`-` counts in `--numstat`), excluding
+/// deletions.
+pub fn get_staged_binary_files(cwd: &str) -> Result<Vec<String>> {
+    let output = run_git_command("git diff --staged --numstat --diff-filter=d", cwd)?;
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.splitn(3, '\t').collect();
+            match parts.as_slice() {
+                ["-", "-", path] => Some(path.to_string()),
+                _ => None,
+            }
+        })
+        .collect())
+}
+
+pub fn get_staged_diff(cwd: &str) -> Result<String> {
+    run_git_command(
+        "git diff --staged --unified=0 --no-color --no-ext-diff",
+        cwd,
+    )
 }
 
 pub fn commit_with_message(cwd: &str, message: &str) -> Result<()> {
@@ -68,9 +126,14 @@ pub fn commit_with_message(cwd: &str, message: &str) -> Result<()> {
     file.write_all(message.as_bytes())?;
     file.flush()?;
 
-    let path = file.path().to_string_lossy().to_string();
-    let cmd = format!("git commit -F \"{}\"", path.replace("\"", "\\\""));
-    run_git_command(&cmd, cwd)?;
+    run_git_args(
+        &[
+            OsStr::new("commit"),
+            OsStr::new("-F"),
+            file.path().as_os_str(),
+        ],
+        cwd,
+    )?;
 
     Ok(())
 }
@@ -87,3 +150,53 @@ pub fn is_git_repository(cwd: &str) -> bool {
 pub fn get_repo_root(cwd: &str) -> Result<String> {
     run_git_command("git rev-parse --show-toplevel", cwd)
 }
+
+/// Resolves a path inside the git directory (`.git/<name>`, worktree aware).
+pub fn get_git_path(cwd: &str, name: &str) -> Result<PathBuf> {
+    let path = run_git_command(&format!("git rev-parse --git-path {}", name), cwd)?;
+    Ok(Path::new(cwd).join(path))
+}
+
+/// The file `commit.template` points at, if one is configured.
+pub fn get_commit_template(cwd: &str) -> Option<PathBuf> {
+    let path = run_git_command("git config --path commit.template", cwd).ok()?;
+    (!path.is_empty()).then(|| Path::new(cwd).join(path))
+}
+
+pub fn get_hooks_dir(cwd: &str) -> Result<PathBuf> {
+    get_git_path(cwd, "hooks")
+}
+
+pub fn get_head_sha(cwd: &str) -> Result<String> {
+    run_git_command("git rev-parse HEAD", cwd)
+}
+
+/// A commit replayed by `bench`.
+#[derive(Debug, Clone)]
+pub struct CommitInfo {
+    pub sha: String,
+    pub message: String,
+}
+
+/// The last `count` non-merge commits reachable from HEAD, newest first.
+pub fn get_recent_commits(cwd: &str, count: usize) -> Result<Vec<CommitInfo>> {
+    let cmd = format!("git log --no-merges -n {} --format=%H%x00%B%x1e", count);
+    let output = run_git_command(&cmd, cwd)?;
+    Ok(output
+        .split('\x1e')
+        .filter_map(|record| {
+            let (sha, message) = record.trim().split_once('\0')?;
+            Some(CommitInfo {
+                sha: sha.to_string(),
+                message: message.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// `--numstat` of `sha` against its first parent, or against the empty tree
+/// for a root commit.
+pub fn get_commit_numstat(cwd: &str, sha: &str) -> Result<String> {
+    let cmd = format!("git diff-tree --no-commit-id --root -r --numstat {}", sha);
+    run_git_command(&cmd, cwd)
+}
@@ -16,7 +16,7 @@ lazy_static::lazy_static! {
             emoji: "🎨".to_string(),
             code: ":art:".to_string(),
             description: "Improve structure/format".to_string(),
-            keywords: vec!["format", "structure", "style", "lint"]
+            keywords: ["format", "structure", "style", "lint"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -25,7 +25,7 @@ lazy_static::lazy_static! {
             emoji: "⚡".to_string(),
             code: ":zap:".to_string(),
             description: "Improve performance".to_string(),
-            keywords: vec!["performance", "speed", "optimize", "fast"]
+            keywords: ["performance", "speed", "optimize", "fast"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -34,7 +34,7 @@ lazy_static::lazy_static! {
             emoji: "🔥".to_string(),
             code: ":fire:".to_string(),
             description: "Remove code/files".to_string(),
-            keywords: vec!["remove", "delete", "clean", "unused"]
+            keywords: ["remove", "delete", "clean", "unused"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -43,7 +43,7 @@ lazy_static::lazy_static! {
             emoji: "🐛".to_string(),
             code: ":bug:".to_string(),
             description: "Fix bug".to_string(),
-            keywords: vec!["fix", "bug", "issue", "error", "crash"]
+            keywords: ["fix", "bug", "issue", "error", "crash"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -52,7 +52,7 @@ lazy_static::lazy_static! {
             emoji: "✨".to_string(),
             code: ":sparkles:".to_string(),
             description: "New feature".to_string(),
-            keywords: vec!["feature", "new", "add", "implement"]
+            keywords: ["feature", "new", "add", "implement"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -61,7 +61,7 @@ lazy_static::lazy_static! {
             emoji: "📝".to_string(),
             code: ":memo:".to_string(),
             description: "Add documentation".to_string(),
-            keywords: vec!["docs", "documentation", "comment", "readme"]
+            keywords: ["docs", "documentation", "comment", "readme"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -70,7 +70,7 @@ lazy_static::lazy_static! {
             emoji: "🚀".to_string(),
             code: ":rocket:".to_string(),
             description: "Deploy stuff".to_string(),
-            keywords: vec!["deploy", "release", "publish", "launch"]
+            keywords: ["deploy", "release", "publish", "launch"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -79,7 +79,7 @@ lazy_static::lazy_static! {
             emoji: "💅".to_string(),
             code: ":nail_care:".to_string(),
             description: "Polish code".to_string(),
-            keywords: vec!["polish", "refine", "improve"]
+            keywords: ["polish", "refine", "improve"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -88,7 +88,7 @@ lazy_static::lazy_static! {
             emoji: "✅".to_string(),
             code: ":white_check_mark:".to_string(),
             description: "Add tests".to_string(),
-            keywords: vec!["test", "tests", "testing"]
+            keywords: ["test", "tests", "testing"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -97,7 +97,7 @@ lazy_static::lazy_static! {
             emoji: "🔐".to_string(),
             code: ":lock:".to_string(),
             description: "Security fix".to_string(),
-            keywords: vec!["security", "auth", "encrypt"]
+            keywords: ["security", "auth", "encrypt"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -106,7 +106,7 @@ lazy_static::lazy_static! {
             emoji: "⬆️".to_string(),
             code: ":arrow_up:".to_string(),
             description: "Upgrade dependencies".to_string(),
-            keywords: vec!["upgrade", "update", "dependency", "dependencies"]
+            keywords: ["upgrade", "update", "dependency", "dependencies"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -115,7 +115,7 @@ lazy_static::lazy_static! {
             emoji: "⬇️".to_string(),
             code: ":arrow_down:".to_string(),
             description: "Downgrade dependencies".to_string(),
-            keywords: vec!["downgrade"]
+            keywords: ["downgrade"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -124,7 +124,7 @@ lazy_static::lazy_static! {
             emoji: "📦".to_string(),
             code: ":package:".to_string(),
             description: "Update packages".to_string(),
-            keywords: vec!["package", "npm", "yarn", "bundler"]
+            keywords: ["package", "npm", "yarn", "bundler"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -133,7 +133,7 @@ lazy_static::lazy_static! {
             emoji: "🔧".to_string(),
             code: ":wrench:".to_string(),
             description: "Configuration".to_string(),
-            keywords: vec!["config", "configuration", "settings"]
+            keywords: ["config", "configuration", "settings"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -142,7 +142,7 @@ lazy_static::lazy_static! {
             emoji: "🌐".to_string(),
             code: ":globe_with_meridians:".to_string(),
             description: "i18n/localization".to_string(),
-            keywords: vec!["i18n", "translation", "locale", "language"]
+            keywords: ["i18n", "translation", "locale", "language"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -151,7 +151,7 @@ lazy_static::lazy_static! {
             emoji: "♿".to_string(),
             code: ":wheelchair:".to_string(),
             description: "Accessibility".to_string(),
-            keywords: vec!["accessibility", "a11y", "aria"]
+            keywords: ["accessibility", "a11y", "aria"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -160,7 +160,7 @@ lazy_static::lazy_static! {
             emoji: "🚨".to_string(),
             code: ":rotating_light:".to_string(),
             description: "Fix warnings".to_string(),
-            keywords: vec!["warning", "lint"]
+            keywords: ["warning", "lint"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -169,7 +169,7 @@ lazy_static::lazy_static! {
             emoji: "🔍".to_string(),
             code: ":mag:".to_string(),
             description: "SEO".to_string(),
-            keywords: vec!["seo"]
+            keywords: ["seo"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -178,7 +178,7 @@ lazy_static::lazy_static! {
             emoji: "🍎".to_string(),
             code: ":apple:".to_string(),
             description: "macOS fix".to_string(),
-            keywords: vec!["macos", "mac", "apple"]
+            keywords: ["macos", "mac", "apple"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -187,7 +187,7 @@ lazy_static::lazy_static! {
             emoji: "🐧".to_string(),
             code: ":penguin:".to_string(),
             description: "Linux fix".to_string(),
-            keywords: vec!["linux", "ubuntu"]
+            keywords: ["linux", "ubuntu"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -197,7 +197,7 @@ lazy_static::lazy_static! {
             emoji: "🐍".to_string(),
             code: ":snake:".to_string(),
             description: "Python changes".to_string(),
-            keywords: vec!["python", "django", "flask", "pip", "pytorch"]
+            keywords: ["python", "django", "flask", "pip", "pytorch"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -206,7 +206,7 @@ lazy_static::lazy_static! {
             emoji: "📚".to_string(),
             code: ":books:".to_string(),
             description: "Node.js/JavaScript".to_string(),
-            keywords: vec!["node", "npm", "javascript", "express", "typescript"]
+            keywords: ["node", "npm", "javascript", "express", "typescript"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -215,7 +215,7 @@ lazy_static::lazy_static! {
             emoji: "🦀".to_string(),
             code: ":crab:".to_string(),
             description: "Rust changes".to_string(),
-            keywords: vec!["rust", "cargo", "tokio", "wasm"]
+            keywords: ["rust", "cargo", "tokio", "wasm"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -224,7 +224,7 @@ lazy_static::lazy_static! {
             emoji: "☕".to_string(),
             code: ":coffee:".to_string(),
             description: "Java changes".to_string(),
-            keywords: vec!["java", "spring", "maven", "gradle", "jvm"]
+            keywords: ["java", "spring", "maven", "gradle", "jvm"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -233,7 +233,7 @@ lazy_static::lazy_static! {
             emoji: "🐳".to_string(),
             code: ":whale:".to_string(),
             description: "Docker changes".to_string(),
-            keywords: vec!["docker", "container", "dockerfile", "image"]
+            keywords: ["docker", "container", "dockerfile", "image"]
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -0,0 +1,210 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::PathBuf;
+
+pub const HOOK_NAME: &str = "prepare-commit-msg";
+
+/// Marker line used to recognise hooks written by Autocommiter so that
+/// foreign hooks are never overwritten or removed by accident.
+const HOOK_MARKER: &str = "# autocommiter-managed-hook";
+
+fn hook_script() -> String {
+    let exe = std::env::current_exe()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "autocommiter".to_string());
+
+    format!(
+        "#!/bin/sh\n\
+         {}\n\
+         # Installed by `autocommiter install-hook`; remove with `autocommiter uninstall-hook`.\n\
+         AUTOCOMMITER=\"{}\"\n\
+         [ -x \"$AUTOCOMMITER\" ] || AUTOCOMMITER=autocommiter\n\
+         command -v \"$AUTOCOMMITER\" >/dev/null 2>&1 || exit 0\n\
+         \"$AUTOCOMMITER\" hook \"$1\" \"$2\" \"$3\" || true\n",
+        HOOK_MARKER,
+        exe.replace('"', "\\\"")
+    )
+}
+
+pub fn get_hook_path(repo_root: &str) -> Result<PathBuf> {
+    Ok(crate::git::get_hooks_dir(repo_root)?.join(HOOK_NAME))
+}
+
+fn is_managed_hook(content: &str) -> bool {
+    content.lines().any(|l| l.trim() == HOOK_MARKER)
+}
+
+pub fn install_hook(repo_root: &str, force: bool) -> Result<PathBuf> {
+    let hook_path = get_hook_path(repo_root)?;
+
+    if hook_path.exists() {
+        let existing = fs::read_to_string(&hook_path).unwrap_or_default();
+        if !is_managed_hook(&existing) {
+            if !force {
+                return Err(anyhow!(
+                    "A {} hook already exists at {}. Use --force to replace it.",
+                    HOOK_NAME,
+                    hook_path.display()
+                ));
+            }
+            let backup = hook_path.with_extension("bak");
+            fs::copy(&hook_path, &backup)?;
+        }
+    }
+
+    if let Some(parent) = hook_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&hook_path, hook_script())?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(hook_path)
+}
+
+/// Removes the hook if it was installed by Autocommiter. Returns `false` when
+/// there was no hook to remove.
+pub fn uninstall_hook(repo_root: &str) -> Result<bool> {
+    let hook_path = get_hook_path(repo_root)?;
+
+    if !hook_path.exists() {
+        return Ok(false);
+    }
+
+    let existing = fs::read_to_string(&hook_path).unwrap_or_default();
+    if !is_managed_hook(&existing) {
+        return Err(anyhow!(
+            "The {} hook at {} was not installed by Autocommiter; leaving it untouched.",
+            HOOK_NAME,
+            hook_path.display()
+        ));
+    }
+
+    fs::remove_file(&hook_path)?;
+    Ok(true)
+}
+
+/// Git passes the message source as the second hook argument. It is empty
+/// for a plain `git commit`; `message`, `merge`, `squash` and `commit`
+/// (amend, `-c`, `-C`) all mean a message already exists. `template` is
+/// checked separately with `is_unedited_template`.
+pub fn should_generate_for_source(source: Option<&str>) -> bool {
+    source.map(|s| s.trim().is_empty()).unwrap_or(true)
+}
+
+/// Line after which `git commit -v` appends the diff; git discards
+/// everything from here on.
+const SCISSORS_LINE: &str = "# ------------------------ >8 ------------------------";
+
+/// True when the message file holds nothing but blank lines and comments
+/// above the scissors line.
+pub fn is_message_empty(content: &str) -> bool {
+    content
+        .lines()
+        .map(|l| l.trim())
+        .take_while(|l| *l != SCISSORS_LINE)
+        .all(|l| l.is_empty() || l.starts_with('#'))
+}
+
+/// Non-comment lines above the scissors line, ignoring blank ones.
+fn message_lines(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .map(|l| l.trim())
+        .take_while(|l| *l != SCISSORS_LINE)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .collect()
+}
+
+/// True when a file pre-filled from `commit.template` still holds only the
+/// template text and comments, so no message has been written yet.
+pub fn is_unedited_template(content: &str, template: &str) -> bool {
+    message_lines(content) == message_lines(template)
+}
+
+/// Turns the template text above the scissors line into comments, so it
+/// stays visible in the editor but is not part of the message.
+pub fn comment_out_template(content: &str) -> String {
+    let mut below_scissors = false;
+    content
+        .lines()
+        .map(|line| {
+            below_scissors |= line.trim() == SCISSORS_LINE;
+            if below_scissors || line.trim().is_empty() || line.trim_start().starts_with('#') {
+                line.to_string()
+            } else {
+                format!("# {}", line)
+            }
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Writes `message` at the top of the message file, keeping git's comment
+/// block (status summary, instructions) below it.
+pub fn write_message_file(path: &str, message: &str, existing: &str) -> Result<()> {
+    let content = if existing.trim().is_empty() {
+        format!("{}\n", message)
+    } else {
+        format!("{}\n\n{}", message, existing.trim_start_matches('\n'))
+    };
+    fs::write(path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = "\n# Please enter the commit message for your changes. Lines starting\n# with '#' will be ignored.\n#\n# Changes to be committed:\n#\tmodified:   src/lib.rs\n#\n";
+
+    #[test]
+    fn test_is_message_empty() {
+        assert!(is_message_empty(""));
+        assert!(is_message_empty(TEMPLATE));
+        assert!(!is_message_empty(&format!("fix: keep this\n{}", TEMPLATE)));
+
+        // `git commit -v`: the diff below the scissors line is not part of
+        // the message, even though its lines do not start with '#'.
+        let verbose = format!(
+            "{}{}\n# Do not modify or remove the line above.\ndiff --git a/src/lib.rs b/src/lib.rs\n+pub mod hooks;\n",
+            TEMPLATE, SCISSORS_LINE
+        );
+        assert!(is_message_empty(&verbose));
+        assert!(!is_message_empty(&format!("fix: keep this\n{}", verbose)));
+    }
+
+    #[test]
+    fn test_unedited_template() {
+        let template = "feat:\n\nWhy:\n";
+        let filled = format!("{}{}", template, TEMPLATE);
+        assert!(is_unedited_template(&filled, template));
+        assert!(!is_unedited_template(
+            &format!("feat: add hooks\n\nWhy:\n{}", TEMPLATE),
+            template
+        ));
+
+        let commented = comment_out_template(&filled);
+        assert!(commented.starts_with("# feat:\n\n# Why:\n"));
+        assert!(is_message_empty(&commented));
+    }
+
+    #[test]
+    fn test_write_message_file_keeps_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("COMMIT_EDITMSG");
+        let path = path.to_str().unwrap();
+
+        write_message_file(path, "feat: add hooks", TEMPLATE).unwrap();
+        let written = fs::read_to_string(path).unwrap();
+        assert!(written.starts_with("feat: add hooks\n\n# Please enter"));
+        assert!(written.ends_with(TEMPLATE.trim_start_matches('\n')));
+
+        write_message_file(path, "feat: add hooks", "").unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "feat: add hooks\n");
+    }
+}
@@ -8,17 +8,38 @@
 //!
 //! High-level responsibilities by module:
 //! - `api_client` — HTTP/inference API calls and commit-message generation.
+//! - `bench` — Report types and Markdown rendering for `bench`, which replays
+//!   recent commits through several models.
 //! - `changes_summarizer` — Builds lightweight summaries of staged changes and
 //!   compresses them into a small JSON payload to send to the model service.
-//! - `config` — Load/save user configuration stored in the home directory
-//!   (`~/.autocommiter.json`). Provides defaults and helpers for common config
+//! - `config` — Load/save user configuration stored in the XDG config
+//!   directory (`~/.config/autocommiter/config.json`), layered with
+//!   per-repository files. Provides defaults and helpers for common config
 //!   operations (API key, selected model, flags).
+//! - `credentials` — Resolves the API key from a 0600 key file, an external
+//!   command, git's credential helper or the environment, so it never has to
+//!   sit in the config file.
+//! - `file_guard` — Flags oversized and binary staged files before a commit
+//!   and suggests Git LFS patterns.
 //! - `git` — Thin wrapper around git command invocations used to stage,
 //!   inspect, commit and push changes.
 //! - `gitmoji` — Utilities to select or guess a gitmoji and prepend it to
 //!   generated messages.
+//! - `hooks` — Install/remove the `prepare-commit-msg` git hook and helpers
+//!   used by the non-interactive `hook` mode.
 //! - `model_manager` — Fetch and cache available models from the Models API and
 //!   expose a local cached list.
+//! - `secret_scanner` — Scans staged diffs for credentials (cloud keys, tokens,
+//!   private keys, `.env` contents) before anything is sent or committed.
+//! - `path_match` — Gitignore-style glob matching for repo-relative paths.
+//! - `paths` — XDG config/cache directories and migration of files from
+//!   their old locations in the home directory.
+//! - `privacy` — Per-repository `.autocommiter-private` globs for files the
+//!   model must never see; they are dropped or replaced by a placeholder.
+//! - `redactor` — Masks secrets, emails, IP addresses and user-configured
+//!   patterns in the summary before it is sent to the inference API.
+//! - `output` — Text/JSON output selection (`--output json`) and small
+//!   formatting helpers shared by the CLI commands.
 //!
 //! Usage (short):
 //! ```text
@@ -29,15 +50,26 @@
 //! ```
 //!
 //! Configuration:
-//! - The config file is stored at `~/.autocommiter.json` and contains fields
+//! - The config file is stored at `$XDG_CONFIG_HOME/autocommiter/config.json`
+//!   (or the path in `AUTOCOMMITER_CONFIG`) and contains fields
 //!   like `api_key`, `selected_model`, `enable_gitmoji` and
 //!   `update_gitignore`.
 //!
 //! See the `docs/` directory for architecture notes and usage examples.
 
 pub mod api_client;
+pub mod bench;
 pub mod changes_summarizer;
 pub mod config;
+pub mod credentials;
+pub mod file_guard;
 pub mod git;
 pub mod gitmoji;
+pub mod hooks;
 pub mod model_manager;
+pub mod output;
+pub mod path_match;
+pub mod paths;
+pub mod privacy;
+pub mod redactor;
+pub mod secret_scanner;
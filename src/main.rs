@@ -1,19 +1,48 @@
 mod api_client;
+mod bench;
 mod changes_summarizer;
 mod config;
+mod credentials;
+mod file_guard;
 mod git;
 mod gitmoji;
+mod hooks;
 mod model_manager;
+mod output;
+mod path_match;
+mod paths;
+mod privacy;
+mod redactor;
+mod secret_scanner;
 
 use anyhow::{anyhow, Result};
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use colored::*;
+use output::OutputFormat;
+use serde_json::json;
 use std::io::{self, Write};
 
 #[derive(Parser)]
 #[command(name = "autocommiter")]
 #[command(about = "Auto-generate git commit messages using AI", long_about = None)]
 struct Cli {
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        help = "Output format"
+    )]
+    output: OutputFormat,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help = "Configuration profile to use (overrides AUTOCOMMITER_PROFILE)"
+    )]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -34,6 +63,25 @@ enum Commands {
 
         #[arg(short, long, help = "Don't ask for confirmation before committing")]
         force: bool,
+
+        #[arg(
+            long,
+            help = "Show what would be sent to the model without staging, calling the API or committing"
+        )]
+        dry_run: bool,
+
+        #[command(flatten)]
+        overrides: ModelOverrides,
+    },
+
+    #[command(about = "Print a commit message for the staged changes without committing")]
+    Message {
+        #[arg(
+            short,
+            long,
+            help = "Path to git repository (defaults to current directory)"
+        )]
+        repo: Option<String>,
     },
 
     #[command(name = "set-api-key", about = "Set GitHub API key")]
@@ -52,10 +100,29 @@ enum Commands {
     RefreshModels,
 
     #[command(name = "list-models", about = "List available AI models")]
-    ListModels,
+    ListModels {
+        #[arg(
+            long,
+            value_name = "TEXT",
+            help = "Only models whose ID or name fuzzily matches TEXT"
+        )]
+        filter: Option<String>,
+
+        #[arg(long, help = "Only models from this publisher")]
+        publisher: Option<String>,
+
+        #[arg(long, help = "Only models with this tag")]
+        tag: Option<String>,
+    },
 
     #[command(name = "select-model", about = "Select default AI model")]
-    SelectModel,
+    SelectModel {
+        #[arg(
+            value_name = "MODEL",
+            help = "Model ID or a fuzzy match of its name (prompts when omitted)"
+        )]
+        model: Option<String>,
+    },
 
     #[command(name = "get-model", about = "Get current default model")]
     GetModel,
@@ -74,40 +141,379 @@ enum Commands {
 
     #[command(name = "reset-config", about = "Reset configuration to defaults")]
     ResetConfig,
+
+    #[command(about = "Get, set and list individual configuration values")]
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    #[command(
+        name = "install-hook",
+        about = "Install a prepare-commit-msg hook that fills in commit messages"
+    )]
+    InstallHook {
+        #[arg(
+            short,
+            long,
+            help = "Path to git repository (defaults to current directory)"
+        )]
+        repo: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            help = "Replace an existing hook not managed by Autocommiter"
+        )]
+        force: bool,
+    },
+
+    #[command(
+        name = "uninstall-hook",
+        about = "Remove the Autocommiter prepare-commit-msg hook"
+    )]
+    UninstallHook {
+        #[arg(
+            short,
+            long,
+            help = "Path to git repository (defaults to current directory)"
+        )]
+        repo: Option<String>,
+    },
+
+    #[command(about = "Replay recent commits through several models and compare their messages")]
+    Bench {
+        #[arg(
+            short,
+            long,
+            help = "Path to git repository (defaults to current directory)"
+        )]
+        repo: Option<String>,
+
+        #[arg(
+            short = 'n',
+            long,
+            default_value_t = bench::DEFAULT_BENCH_COMMITS,
+            help = "Number of recent commits to replay"
+        )]
+        commits: usize,
+
+        #[arg(
+            long = "model",
+            value_name = "MODEL",
+            help = "Model or alias to compare; repeatable (defaults to selected_model and fallback_models)"
+        )]
+        models: Vec<String>,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Write the report to FILE: JSON for .json, Markdown otherwise"
+        )]
+        report: Option<String>,
+    },
+
+    #[command(
+        about = "Fill a commit message file from staged changes (used by the git hook)",
+        hide = true
+    )]
+    Hook {
+        #[arg(value_name = "MSG_FILE", help = "Path to the commit message file")]
+        msg_file: String,
+
+        #[arg(value_name = "SOURCE", help = "Source of the commit message")]
+        source: Option<String>,
+
+        #[arg(value_name = "SHA", help = "Commit SHA when amending")]
+        sha: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    #[command(about = "Print the value of a setting")]
+    Get {
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        #[command(flatten)]
+        scope: ScopeArgs,
+    },
+
+    #[command(about = "Set a setting (lists take comma-separated values or a JSON array)")]
+    Set {
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        #[arg(value_name = "VALUE")]
+        value: String,
+
+        #[command(flatten)]
+        scope: ScopeArgs,
+    },
+
+    #[command(about = "Remove a setting so lower layers or the default apply")]
+    Unset {
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        #[command(flatten)]
+        scope: ScopeArgs,
+    },
+
+    #[command(about = "Append values to a list setting")]
+    Add {
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        #[arg(value_name = "VALUE", required = true)]
+        values: Vec<String>,
+
+        #[command(flatten)]
+        scope: ScopeArgs,
+    },
+
+    #[command(about = "Remove values from a list setting")]
+    Remove {
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        #[arg(value_name = "VALUE", required = true)]
+        values: Vec<String>,
+
+        #[command(flatten)]
+        scope: ScopeArgs,
+    },
+
+    #[command(about = "List all settings")]
+    List {
+        #[command(flatten)]
+        scope: ScopeArgs,
+    },
+
+    #[command(about = "Check config files against the schema and report problems")]
+    Validate {
+        #[command(flatten)]
+        scope: ScopeArgs,
+    },
+}
+
+#[derive(Args)]
+struct ScopeArgs {
+    #[arg(
+        long,
+        conflicts_with = "local",
+        help = "Use the global config file (default for changes)"
+    )]
+    global: bool,
+
+    #[arg(long, help = "Use this clone's untracked .git/autocommiter.json")]
+    local: bool,
+}
+
+impl ScopeArgs {
+    /// Scope named on the command line, if any.
+    fn explicit(&self) -> Option<config::ConfigScope> {
+        if self.local {
+            Some(config::ConfigScope::Local)
+        } else if self.global {
+            Some(config::ConfigScope::Global)
+        } else {
+            None
+        }
+    }
+
+    fn for_write(&self) -> config::ConfigScope {
+        self.explicit().unwrap_or(config::ConfigScope::Global)
+    }
+}
+
+/// Model settings for a single `generate` run; the config is not changed.
+#[derive(Args, Default)]
+struct ModelOverrides {
+    #[arg(
+        long,
+        value_name = "MODEL",
+        help = "Model or alias to use instead of selected_model"
+    )]
+    model: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PROVIDER",
+        help = "Provider for the model (github or openai)"
+    )]
+    provider: Option<String>,
+
+    #[arg(long, help = "Sampling temperature (0-2)")]
+    temperature: Option<f32>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Maximum tokens in the generated message"
+    )]
+    max_tokens: Option<u32>,
+}
+
+impl ModelOverrides {
+    /// Replaces `selected_model` in `config` with `--model`/`--provider`.
+    fn apply(&self, config: &mut config::Config) -> Result<()> {
+        if self.model.is_none() && self.provider.is_none() {
+            return Ok(());
+        }
+        let model = match &self.model {
+            Some(model) => config.resolve_model(model),
+            None => config.effective_model(),
+        };
+        let mut model = api_client::ModelRef::parse(&model)?;
+        if let Some(provider) = &self.provider {
+            model.provider = api_client::Provider::parse(provider)?;
+        }
+        config.selected_model = Some(model.to_string());
+        Ok(())
+    }
+
+    fn options(&self) -> Result<api_client::GenerationOptions> {
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err(anyhow!(
+                    "--temperature must be between 0 and 2, got {}",
+                    temperature
+                ));
+            }
+        }
+        Ok(api_client::GenerationOptions {
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+        })
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::WARN)
+        .with_writer(io::stderr)
         .init();
 
     let cli = Cli::parse();
+    let output = cli.output;
+    let profile = cli.profile.as_deref();
 
-    match cli.command {
+    let result = match cli.command {
+        Some(Commands::Generate {
+            repo,
+            dry_run: true,
+            overrides,
+            ..
+        }) => dry_run(repo.as_deref(), &overrides, profile, output).await,
         Some(Commands::Generate {
             repo,
             no_push,
             force,
-        }) => generate_commit(repo.as_deref(), no_push, force).await,
-        Some(Commands::SetApiKey { key }) => set_api_key(key).await,
-        Some(Commands::GetApiKey) => get_api_key(),
-        Some(Commands::RefreshModels) => refresh_models().await,
-        Some(Commands::ListModels) => list_models(),
-        Some(Commands::SelectModel) => select_model().await,
-        Some(Commands::GetModel) => get_model(),
-        Some(Commands::ToggleGitmoji) => toggle_gitmoji(),
-        Some(Commands::ToggleSkipConfirmation) => toggle_skip_confirmation(),
-        Some(Commands::GetConfig) => get_config(),
-        Some(Commands::ResetConfig) => reset_config(),
-        None => generate_commit(None, false, false).await,
-    }
-}
-
-async fn generate_commit(repo_path: Option<&str>, no_push: bool, force: bool) -> Result<()> {
+            overrides,
+            ..
+        }) => generate_commit(repo.as_deref(), no_push, force, &overrides, profile, output).await,
+        Some(Commands::Message { repo }) => print_message(repo.as_deref(), profile, output).await,
+        Some(Commands::SetApiKey { key }) => set_api_key(key, profile, output).await,
+        Some(Commands::GetApiKey) => get_api_key(profile, output),
+        Some(Commands::RefreshModels) => refresh_models(profile, output).await,
+        Some(Commands::ListModels {
+            filter,
+            publisher,
+            tag,
+        }) => {
+            list_models(
+                filter.as_deref(),
+                publisher.as_deref(),
+                tag.as_deref(),
+                profile,
+                output,
+            )
+            .await
+        }
+        Some(Commands::SelectModel { model }) => {
+            select_model(model.as_deref(), profile, output).await
+        }
+        Some(Commands::GetModel) => get_model(profile, output),
+        Some(Commands::ToggleGitmoji) => {
+            toggle_setting("enable_gitmoji", "Gitmoji", profile, output)
+        }
+        Some(Commands::ToggleSkipConfirmation) => {
+            toggle_setting("skip_confirmation", "Skip Confirmation", profile, output)
+        }
+        Some(Commands::GetConfig) => get_config(profile, output),
+        Some(Commands::ResetConfig) => reset_config(profile, output),
+        Some(Commands::Config { action }) => config_command(action, profile, output),
+        Some(Commands::InstallHook { repo, force }) => install_hook(repo.as_deref(), force, output),
+        Some(Commands::UninstallHook { repo }) => uninstall_hook(repo.as_deref(), output),
+        Some(Commands::Bench {
+            repo,
+            commits,
+            models,
+            report,
+        }) => {
+            bench_models(
+                repo.as_deref(),
+                commits,
+                &models,
+                report.as_deref(),
+                profile,
+                output,
+            )
+            .await
+        }
+        Some(Commands::Hook {
+            msg_file, source, ..
+        }) => run_hook(&msg_file, source.as_deref(), profile).await,
+        None => {
+            generate_commit(
+                None,
+                false,
+                false,
+                &ModelOverrides::default(),
+                profile,
+                output,
+            )
+            .await
+        }
+    };
+
+    // In JSON mode failures are reported as a JSON document as well, so
+    // wrappers only ever need to parse stdout.
+    if let Err(e) = &result {
+        if output.is_json() {
+            output::print_json(&json!({ "success": false, "error": e.to_string() }))?;
+            std::process::exit(1);
+        }
+    }
+    result
+}
+
+/// Result of the summarize + generate steps, shared by `generate`,
+/// `message` and the git hook.
+struct GeneratedMessage {
+    message: String,
+    /// Model that produced the message; `None` when the local fallback was used.
+    model: Option<String>,
+    summary_json: String,
+}
+
+async fn generate_commit(
+    repo_path: Option<&str>,
+    no_push: bool,
+    force: bool,
+    overrides: &ModelOverrides,
+    profile: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
     let cwd = repo_path.unwrap_or(".");
+    // Bad flags should fail before anything is staged
+    let options = overrides.options()?;
 
-    println!("{}", "🪄 Autocommiter: Generating commit...".cyan());
+    output.status("🪄 Autocommiter: Generating commit...".cyan());
 
     // Check if it's a git repo
     if !git::is_git_repository(cwd) {
@@ -115,335 +521,2039 @@ async fn generate_commit(repo_path: Option<&str>, no_push: bool, force: bool) ->
     }
 
     let repo_root = git::get_repo_root(cwd)?;
-    println!("{} {}", "📂 Repository:".cyan(), repo_root.dimmed());
+    output.status(format!(
+        "{} {}",
+        "📂 Repository:".cyan(),
+        repo_root.dimmed()
+    ));
+
+    // An unknown `--provider` or `--model` must also fail before staging
+    let layered = config::load_layered_config(Some(&repo_root), profile)?;
+    let mut config = layered.config.clone();
+    overrides.apply(&mut config)?;
 
     // Ensure gitignore safety
-    println!("{}", "🛡️  Ensuring .gitignore safety...".cyan());
-    ensure_gitignore_safety(&repo_root)?;
+    output.status("🛡️  Ensuring .gitignore safety...".cyan());
+    ensure_gitignore_safety(&repo_root, &config)?;
 
     // Stage changes
-    println!("{}", "📦 Staging changes...".cyan());
+    output.status("📦 Staging changes...".cyan());
     git::stage_all_changes(&repo_root)?;
 
+    // `skip_confirmation` only covers the final commit prompt; the safety
+    // checks still ask unless `--force` is given.
+    let interactive = !force;
+    let confirm_commit = interactive && !config.skip_confirmation.unwrap_or(false);
+
+    // Catch files that should be ignored but are already in the index. Part
+    // of the opt-in gitignore safety step, like the `.gitignore` update.
+    if config.update_gitignore.unwrap_or(false) {
+        check_tracked_ignored_files(&repo_root, &config, interactive, output)?;
+    }
+
     // Check for staged files
-    println!("{}", "📋 Checking staged changes...".cyan());
+    output.status("📋 Checking staged changes...".cyan());
     let staged_files = git::get_staged_files(&repo_root)?;
     if staged_files.is_empty() {
-        println!(
-            "{}",
-            "ℹ️  No changes to commit — Autocommit skipped.".yellow()
-        );
+        output.status("ℹ️  No changes to commit — Autocommit skipped.".yellow());
+        if output.is_json() {
+            output::print_json(&json!({
+                "success": true,
+                "repository": repo_root,
+                "staged_files": staged_files,
+                "committed": false,
+            }))?;
+        }
         return Ok(());
     }
 
-    println!("{} {} files", "✓ Found".green(), staged_files.len());
+    output.status(format!(
+        "{} {} files",
+        "✓ Found".green(),
+        staged_files.len()
+    ));
     for file in &staged_files {
-        println!("  - {}", file.dimmed());
+        output.status(format!("  - {}", file.dimmed()));
     }
 
+    // Large and binary files need an explicit go-ahead
+    check_large_and_binary_files(&repo_root, &config, &staged_files, interactive, output)?;
+
+    // Scan for secrets before anything leaves the machine or gets committed
+    output.status("🔐 Scanning staged changes for secrets...".cyan());
+    check_staged_secrets(&repo_root, &config)?;
+
     // Generate message
-    let message = generate_message(&repo_root).await?;
-    println!("{} {}", "💬 Message:".cyan(), message.italic());
+    let github_key = credentials::resolve_api_key(&layered)?.map(|k| k.key);
+    let generated = generate_message(
+        &repo_root,
+        &config,
+        github_key.as_deref(),
+        options,
+        interactive,
+        output,
+    )
+    .await?;
+    let message = generated.message.clone();
+    output.status(format!("{} {}", "💬 Message:".cyan(), message.italic()));
+
+    let mut report = json!({
+        "success": true,
+        "repository": repo_root,
+        "staged_files": staged_files,
+        "summary": summary_value(&generated.summary_json),
+        "model": generated.model,
+        "message": message,
+        "committed": false,
+        "commit": null,
+        "pushed": false,
+    });
 
     // Ask for confirmation if not forced and config allows
-    let config = config::load_config()?;
-    let skip_conf = config.skip_confirmation.unwrap_or(false);
-    if !force && !skip_conf {
-        print!("{}", "\n🤔 Proceed with commit? (y/n): ".cyan());
-        io::stdout().flush()?;
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("{}", "❌ Cancelled.".red());
-            return Ok(());
+    if confirm_commit && !confirm("\n🤔 Proceed with commit? (y/n): ", output)? {
+        output.status("❌ Cancelled.".red());
+        if output.is_json() {
+            output::print_json(&report)?;
         }
+        return Ok(());
     }
 
     // Commit
-    println!("{}", "✍️  Committing changes...".cyan());
+    output.status("✍️  Committing changes...".cyan());
     git::commit_with_message(&repo_root, &message)?;
-    println!("{}", "✓ Commit successful!".green());
+    output.status("✓ Commit successful!".green());
+    report["committed"] = json!(true);
+    report["commit"] = json!(git::get_head_sha(&repo_root).ok());
 
     // Push
     if !no_push {
-        println!("{}", "🚀 Pushing to remote...".cyan());
-        git::push_changes(&repo_root)?;
-        println!("{}", "✓ Push successful!".green());
-    }
-
-    println!("{}", "✨ Done!".green().bold());
-    Ok(())
-}
-
-async fn generate_message(repo_root: &str) -> Result<String> {
-    let config = config::load_config()?;
-
-    // Try API-based generation if API key exists
-    if let Some(api_key) = &config.api_key {
-        if let Ok(message) = try_api_generation(repo_root, api_key, &config).await {
-            return Ok(message);
+        output.status("🚀 Pushing to remote...".cyan());
+        match git::push_changes(&repo_root) {
+            Ok(()) => {
+                output.status("✓ Push successful!".green());
+                report["pushed"] = json!(true);
+            }
+            // The commit already exists, so JSON consumers get the push error
+            // alongside the commit SHA instead of a bare failure.
+            Err(e) if output.is_json() => {
+                report["push_error"] = json!(e.to_string());
+            }
+            Err(e) => return Err(e),
         }
     }
 
-    // Fallback to local generation
-    Ok("chore: automated commit generated by Autocommiter".to_string())
+    output.status("✨ Done!".green().bold());
+    if output.is_json() {
+        output::print_json(&report)?;
+    }
+    Ok(())
 }
 
-async fn try_api_generation(
+/// Flags staged files over `max_file_size_kb` or detected as binary. They
+/// need confirmation in interactive runs and abort forced runs unless
+/// `allow_large_files` / `allow_binary_files` is set.
+fn check_large_and_binary_files(
     repo_root: &str,
-    api_key: &str,
     config: &config::Config,
-) -> Result<String> {
-    let model = config
-        .selected_model
-        .clone()
-        .unwrap_or_else(|| "gpt-4o-mini".to_string());
-
-    println!(
-        "{} {}...",
-        "🤖 Generating with model:".cyan(),
-        model.dimmed()
-    );
-
-    let file_changes = changes_summarizer::build_file_changes(repo_root).await?;
-    let file_names = file_changes
-        .iter()
-        .take(50)
-        .map(|f| f.file.as_str())
-        .collect::<Vec<_>>()
-        .join("\n");
-    let compressed_json = changes_summarizer::compress_to_json(&file_changes, 400);
+    staged_files: &[String],
+    interactive: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let limits = file_guard::GuardLimits {
+        max_file_size_kb: config
+            .max_file_size_kb
+            .unwrap_or(file_guard::DEFAULT_MAX_FILE_SIZE_KB),
+        allow_large_files: config.allow_large_files.unwrap_or(false),
+        allow_binary_files: config.allow_binary_files.unwrap_or(false),
+    };
+    let flagged = file_guard::find_flagged_files(repo_root, staged_files, &limits)?;
+    if flagged.is_empty() {
+        return Ok(());
+    }
 
-    let message =
-        api_client::generate_commit_message(api_key, &file_names, &compressed_json, &model).await?;
+    output.status(format!(
+        "{} {} staged file(s) are large or binary:",
+        "⚠️ ".yellow(),
+        flagged.len()
+    ));
+    for file in &flagged {
+        let kind = match (file.large, file.binary) {
+            (true, true) => "large, binary",
+            (true, false) => "large",
+            _ => "binary",
+        };
+        output.status(format!(
+            "  - {} ({}, {})",
+            file.path.yellow(),
+            file_guard::format_size(file.size),
+            kind
+        ));
+    }
+    output.status("💡 Consider Git LFS:".cyan());
+    for pattern in file_guard::lfs_track_patterns(&flagged) {
+        output.status(format!("  git lfs track \"{}\"", pattern).dimmed());
+    }
 
-    // Apply gitmoji if enabled
-    let message = if config.enable_gitmoji.unwrap_or(false) {
-        gitmoji::get_gitmojified_message(&message)
-    } else {
-        message
-    };
+    if !interactive {
+        return Err(anyhow!(
+            "Aborted: large or binary files are staged. Unstage them, track them with Git LFS, or set allow_large_files / allow_binary_files in the config."
+        ));
+    }
+    if !confirm("\n📦 Commit these files anyway? (y/n): ", output)? {
+        return Err(anyhow!("Aborted: large or binary files are staged."));
+    }
+    Ok(())
+}
 
-    Ok(message)
+/// Fails with a report when the staged diff appears to contain credentials.
+fn check_staged_secrets(repo_root: &str, config: &config::Config) -> Result<()> {
+    let allowlist = config.secret_allowlist.clone().unwrap_or_default();
+    let findings = secret_scanner::scan_staged_changes(repo_root, &allowlist)?;
+    if findings.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!(secret_scanner::format_report(&findings)))
 }
 
-async fn set_api_key(key: Option<String>) -> Result<()> {
-    let key_to_set = if let Some(key) = key {
-        key
+/// Reads a line from stdin after showing `prompt`. The prompt is written to
+/// stderr in JSON mode to keep stdout parseable.
+fn prompt_line(prompt: &str, output: OutputFormat) -> Result<String> {
+    if output.is_json() {
+        eprint!("{}", prompt.cyan());
+        io::stderr().flush()?;
     } else {
-        print!(
-            "{}",
-            "Enter GitHub API key (will be stored securely): ".cyan()
-        );
+        print!("{}", prompt.cyan());
         io::stdout().flush()?;
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        input.trim().to_string()
-    };
-
-    if key_to_set.is_empty() {
-        return Err(anyhow!("API key cannot be empty"));
     }
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
 
-    config::set_api_key(key_to_set)?;
-    println!("{}", "✓ API key saved!".green());
-    Ok(())
+fn confirm(prompt: &str, output: OutputFormat) -> Result<bool> {
+    Ok(prompt_line(prompt, output)?.eq_ignore_ascii_case("y"))
 }
 
-fn get_api_key() -> Result<()> {
-    match config::get_api_key()? {
-        Some(key) => {
-            let masked = if key.len() > 8 {
-                format!("{}...{}", &key[..4], &key[key.len() - 4..])
-            } else {
-                "****".to_string()
-            };
-            println!("{} {}", "🔑 API Key:".cyan(), masked.yellow());
-            Ok(())
-        }
-        None => {
-            println!(
-                "{}",
-                "ℹ️  No API key set. Use 'set-api-key' to add one.".yellow()
-            );
-            Ok(())
-        }
-    }
+fn summary_value(summary_json: &str) -> serde_json::Value {
+    serde_json::from_str(summary_json).unwrap_or_else(|_| json!(summary_json))
 }
 
-async fn refresh_models() -> Result<()> {
-    let api_key = config::get_api_key()?
-        .ok_or_else(|| anyhow!("API key not set. Use 'set-api-key' first."))?;
+async fn print_message(
+    repo_path: Option<&str>,
+    profile: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let cwd = repo_path.unwrap_or(".");
 
-    println!("{}", "🔄 Fetching models from GitHub Models API...".cyan());
-    let (success, message, count) = model_manager::refresh_model_list(&api_key).await?;
+    if !git::is_git_repository(cwd) {
+        return Err(anyhow!("Not a git repository"));
+    }
 
-    if success {
-        println!("{} {} models cached", "✓".green(), count);
+    let repo_root = git::get_repo_root(cwd)?;
+    let staged_files = git::get_staged_files(&repo_root)?;
+    if staged_files.is_empty() {
+        return Err(anyhow!("No staged changes"));
+    }
+
+    eprintln!(
+        "{} {} staged files",
+        "📋 Summarizing".cyan(),
+        staged_files.len()
+    );
+    let layered = config::load_layered_config(Some(&repo_root), profile)?;
+    check_staged_secrets(&repo_root, &layered.config)?;
+
+    let github_key = credentials::resolve_api_key(&layered)?.map(|k| k.key);
+    let generated = generate_message(
+        &repo_root,
+        &layered.config,
+        github_key.as_deref(),
+        api_client::GenerationOptions::default(),
+        false,
+        output,
+    )
+    .await?;
+    // The placeholder is only useful for an interactive commit; piped into
+    // `git commit -F -` it would be committed as is.
+    if generated.model.is_none() {
+        return Err(anyhow!(
+            "No commit message was generated: no API key is set or every configured model failed"
+        ));
+    }
+    if output.is_json() {
+        output::print_json(&json!({
+            "success": true,
+            "repository": repo_root,
+            "staged_files": staged_files,
+            "summary": summary_value(&generated.summary_json),
+            "model": generated.model,
+            "message": generated.message,
+        }))?;
     } else {
-        println!("{} {}", "✗".red(), message);
+        println!("{}", generated.message);
     }
     Ok(())
 }
 
-fn list_models() -> Result<()> {
-    let models = model_manager::list_available_models()?;
-    let current = config::get_selected_model()?;
+async fn dry_run(
+    repo_path: Option<&str>,
+    overrides: &ModelOverrides,
+    profile: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let cwd = repo_path.unwrap_or(".");
 
-    println!("{}\n", "📋 Available Models:".cyan().bold());
-    for model in models {
-        let marker = if model.id == current { "→" } else { " " };
-        println!("{} {}", marker.green(), model.name.cyan());
-        if let Some(friendly) = &model.friendly_name {
-            println!("   {}", friendly.dimmed());
-        }
-        if let Some(summary) = &model.summary {
-            println!("   {}", summary.dimmed());
-        }
-        println!();
+    if !git::is_git_repository(cwd) {
+        return Err(anyhow!("Not a git repository"));
     }
-    Ok(())
-}
 
-async fn select_model() -> Result<()> {
-    let models = model_manager::list_available_models()?;
-    if models.is_empty() {
-        return Err(anyhow!("No models available"));
+    let repo_root = git::get_repo_root(cwd)?;
+    let layered = config::load_layered_config(Some(&repo_root), profile)?;
+    let mut config = layered.config.clone();
+    overrides.apply(&mut config)?;
+    let options = overrides.options()?;
+    let model = config.effective_model();
+    let fallbacks = config.effective_fallback_models();
+
+    let api_key = credentials::resolve_api_key(&layered)?;
+
+    let summary = summarize_staged_changes(&repo_root, &config).await?;
+    let user_prompt = api_client::build_user_prompt(&summary.file_names, &summary.compressed_json);
+    let system_tokens = api_client::estimate_tokens(api_client::SYSTEM_PROMPT);
+    let user_tokens = api_client::estimate_tokens(&user_prompt);
+    let estimated_cost =
+        model_manager::model_info(&api_client::ModelRef::parse(&model)?.to_string())?
+            .and_then(|m| m.estimated_cost(system_tokens + user_tokens, EXPECTED_MESSAGE_TOKENS));
+
+    if output.is_json() {
+        return output::print_json(&json!({
+            "success": true,
+            "dry_run": true,
+            "repository": repo_root,
+            "staged_files": summary.staged_files,
+            "model": model,
+            "fallback_models": fallbacks,
+            "temperature": options.temperature,
+            "max_tokens": options.max_tokens,
+            "api_key_set": api_key.is_some(),
+            "api_key_source": api_key.as_ref().map(|k| k.source),
+            "system_prompt": api_client::SYSTEM_PROMPT,
+            "user_prompt": user_prompt,
+            "file_list": summary.file_names,
+            "summary": summary_value(&summary.compressed_json),
+            "redactions": summary.redactions,
+            "restricted_files": summary.restricted_count,
+            "estimated_tokens": system_tokens + user_tokens,
+            "estimated_cost": estimated_cost,
+            "summary_budget": summary.budget,
+        }));
     }
 
-    println!("{}\n", "🤖 Select a Model:".cyan().bold());
-    for (idx, model) in models.iter().enumerate() {
+    println!(
+        "{}",
+        "🔍 Dry run — nothing will be staged, sent or committed.".cyan()
+    );
+    println!("{} {}", "📂 Repository:".cyan(), repo_root.dimmed());
+
+    if summary.staged_files.is_empty() {
         println!(
-            "{}. {} ({})",
-            idx + 1,
-            model.name.cyan(),
-            model.friendly_name.as_ref().unwrap_or(&model.name).dimmed()
+            "{}",
+            "ℹ️  No staged changes — nothing would be sent.".yellow()
         );
+        return Ok(());
     }
 
-    print!("\n{}", "Enter choice (1-{}): ".cyan());
-    println!("{}", models.len());
-    io::stdout().flush()?;
+    println!(
+        "{} {} files",
+        "📋 Staged:".cyan(),
+        summary.staged_files.len()
+    );
+    for file in &summary.staged_files {
+        println!("  - {}", file.dimmed());
+    }
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let choice: usize = input
-        .trim()
-        .parse()
-        .map_err(|_| anyhow!("Invalid choice"))?;
+    println!("\n{} {}", "🤖 Model:".cyan(), model.yellow());
+    if !fallbacks.is_empty() {
+        println!("{} {}", "🔁 Fallbacks:".cyan(), fallbacks.join(", "));
+    }
+    if let Some(temperature) = options.temperature {
+        println!("{} {}", "🌡️  Temperature:".cyan(), temperature);
+    }
+    if let Some(max_tokens) = options.max_tokens {
+        println!("{} {}", "✂️  Max tokens:".cyan(), max_tokens);
+    }
+    if api_key.is_none() {
+        println!(
+            "{}",
+            "ℹ️  No API key set — the local fallback message would be used instead.".yellow()
+        );
+    }
 
-    if choice < 1 || choice > models.len() {
-        return Err(anyhow!("Choice out of range"));
+    if summary.restricted_count > 0 {
+        println!(
+            "\n{} {} file(s) matched {} and are withheld from the model",
+            "🔒 Restricted:".cyan(),
+            summary.restricted_count,
+            privacy::PRIVACY_FILE
+        );
+    }
+
+    if !summary.redactions.is_empty() {
+        println!(
+            "\n{} {} value(s) masked before sending:",
+            "🙈 Redacted".cyan(),
+            summary.redactions.len()
+        );
+        for r in &summary.redactions {
+            println!(
+                "  - {} ({}) [{}] {}",
+                r.file.dimmed(),
+                r.field,
+                r.kind.yellow(),
+                r.original.dimmed()
+            );
+        }
     }
 
-    let selected = &models[choice - 1];
-    config::set_selected_model(selected.id.clone())?;
-    println!("{} {}", "✓ Selected:".green(), selected.name.cyan());
+    println!("\n{}", "── System prompt ──".cyan().bold());
+    println!("{}", api_client::SYSTEM_PROMPT);
+    println!("\n{}", "── User prompt ──".cyan().bold());
+    println!("{}", user_prompt);
+    println!("\n{}", "── Summary payload ──".cyan().bold());
+    println!("{}", summary.compressed_json);
+
+    println!(
+        "\n{} ~{} ({} system + {} user)",
+        "📏 Estimated tokens:".cyan(),
+        system_tokens + user_tokens,
+        system_tokens,
+        user_tokens
+    );
+    println!(
+        "{} {} characters of summary JSON",
+        "📐 Summary budget:".cyan(),
+        summary.budget
+    );
+    if let Some(cost) = estimated_cost {
+        println!(
+            "{} ~{}",
+            "💵 Estimated cost:".cyan(),
+            model_manager::format_cost(cost)
+        );
+    }
     Ok(())
 }
 
-fn get_model() -> Result<()> {
-    let model = config::get_selected_model()?;
-    println!("{} {}", "🤖 Current Model:".cyan(), model.yellow());
+/// Everything derived from a set of changes (usually the index) that is sent
+/// to the model.
+struct StagedSummary {
+    staged_files: Vec<String>,
+    file_names: String,
+    compressed_json: String,
+    redactions: Vec<redactor::Redaction>,
+    restricted_count: usize,
+    /// Character limit the summary JSON was compressed to.
+    budget: usize,
+}
+
+/// Summary size for `model`, from its context window and output limit when
+/// those are known.
+fn summary_budget_for(model: &str) -> Result<usize> {
+    let spec = api_client::ModelRef::parse(model)?.to_string();
+    let info = model_manager::model_info(&spec)?;
+    Ok(changes_summarizer::summary_budget(
+        info.as_ref().and_then(|m| m.context_window),
+        info.as_ref().and_then(|m| m.max_output_tokens),
+    ))
+}
+
+async fn summarize_staged_changes(
+    repo_root: &str,
+    config: &config::Config,
+) -> Result<StagedSummary> {
+    let budget = summary_budget_for(&config.effective_model())?;
+    let file_changes = changes_summarizer::build_file_changes(repo_root).await?;
+    summarize_file_changes(repo_root, config, file_changes, budget)
+}
+
+/// Applies privacy rules and redaction to `file_changes` and compresses them
+/// to `budget` characters.
+fn summarize_file_changes(
+    repo_root: &str,
+    config: &config::Config,
+    file_changes: Vec<changes_summarizer::FileChange>,
+    budget: usize,
+) -> Result<StagedSummary> {
+    let staged_files = file_changes.iter().map(|f| f.file.clone()).collect();
+
+    // Withhold restricted paths, then redact before anything is serialized
+    // for the model
+    let privacy_mode = privacy::PrivacyMode::parse(config.privacy_mode.as_deref())?;
+    let privacy_rules = privacy::load_rules(repo_root)?;
+    let (file_changes, restricted_count) = privacy::apply_rules(&file_changes, &privacy_rules);
+
+    let redactor = redactor::Redactor::new(config.redact_patterns.as_deref().unwrap_or_default())?;
+    let (mut file_changes, redactions) = redactor.redact_file_changes(&file_changes);
+
+    let mut names: Vec<String> = file_changes
+        .iter()
+        .take(50)
+        .map(|f| f.file.clone())
+        .collect();
+    if restricted_count > 0 && privacy_mode == privacy::PrivacyMode::Placeholder {
+        names.insert(0, privacy::placeholder_text(restricted_count));
+        // First, so payload compression never drops it
+        file_changes.insert(0, privacy::placeholder_entry(restricted_count));
+    }
+    let file_names = names.join("\n");
+    let compressed_json = changes_summarizer::compress_to_json(&file_changes, budget);
+
+    Ok(StagedSummary {
+        staged_files,
+        file_names,
+        compressed_json,
+        redactions,
+        restricted_count,
+        budget,
+    })
+}
+
+/// Generates the message with the API, falling back to a placeholder when
+/// every configured model fails. An unavailable `selected_model` is an error
+/// instead, since the placeholder would only hide the misconfiguration.
+async fn generate_message(
+    repo_root: &str,
+    config: &config::Config,
+    github_key: Option<&str>,
+    options: api_client::GenerationOptions,
+    interactive: bool,
+    output: OutputFormat,
+) -> Result<GeneratedMessage> {
+    let summary = summarize_staged_changes(repo_root, config).await?;
+    let chain = api_client::model_chain(
+        &config.effective_model(),
+        &config.effective_fallback_models(),
+    )?;
+
+    match try_api_generation(
+        github_key,
+        chain,
+        options,
+        config,
+        &summary,
+        interactive,
+        output,
+    )
+    .await
+    {
+        Ok(generated) => return Ok(generated),
+        Err(e) if e.is::<model_manager::UnknownModelError>() => return Err(e),
+        Err(_) => {}
+    }
+
+    // Fallback to local generation
+    Ok(GeneratedMessage {
+        message: "chore: automated commit generated by Autocommiter".to_string(),
+        model: None,
+        summary_json: summary.compressed_json,
+    })
+}
+
+/// Tries each model of `chain` in turn, moving on only when a request fails
+/// with a retryable error such as a rate limit.
+async fn try_api_generation(
+    github_key: Option<&str>,
+    mut chain: Vec<api_client::ModelRef>,
+    options: api_client::GenerationOptions,
+    config: &config::Config,
+    summary: &StagedSummary,
+    interactive: bool,
+    output: OutputFormat,
+) -> Result<GeneratedMessage> {
+    if let (Some(key), api_client::Provider::Github) = (github_key, chain[0].provider) {
+        let mut primary = chain.remove(0);
+        primary.model =
+            ensure_model_available(key, primary.model, config, interactive, output).await?;
+        chain.retain(|m| *m != primary);
+        chain.insert(0, primary);
+    }
+
+    let mut last_error = None;
+    for (i, model) in chain.iter().enumerate() {
+        let Some(api_key) = credentials::provider_api_key(model.provider, github_key) else {
+            if model.provider != api_client::Provider::Github {
+                eprintln!(
+                    "{} No API key for {}; skipping {}",
+                    "ℹ️ ".yellow(),
+                    model.provider.name(),
+                    model
+                );
+            }
+            continue;
+        };
+
+        // Progress goes to stderr so `message` and the git hook keep stdout
+        // and the message file clean.
+        eprintln!(
+            "{} {}...",
+            "🤖 Generating with model:".cyan(),
+            model.to_string().dimmed()
+        );
+
+        let message = match api_client::generate_commit_message(
+            &api_key,
+            &summary.file_names,
+            &summary.compressed_json,
+            model,
+            options,
+        )
+        .await
+        {
+            Ok(message) => message,
+            Err(e) if e.is_retryable() => {
+                eprintln!("{} {} failed: {}", "⚠️ ".yellow(), model, e);
+                last_error = Some(e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if i > 0 {
+            eprintln!("{} {}", "✓ Generated by fallback model".green(), model);
+        }
+
+        // Apply gitmoji if enabled
+        let message = if config.enable_gitmoji.unwrap_or(false) {
+            gitmoji::get_gitmojified_message(&message)
+        } else {
+            message
+        };
+
+        return Ok(GeneratedMessage {
+            message,
+            model: Some(model.to_string()),
+            summary_json: summary.compressed_json.clone(),
+        });
+    }
+
+    Err(last_error
+        .map(Into::into)
+        .unwrap_or_else(|| anyhow!("No API key for any configured model")))
+}
+
+/// Checks `model` against the model list before any request is made. In
+/// interactive runs the closest valid IDs are offered as a replacement for
+/// this run; otherwise an unknown model is an `UnknownModelError`.
+async fn ensure_model_available(
+    api_key: &str,
+    model: String,
+    config: &config::Config,
+    interactive: bool,
+    output: OutputFormat,
+) -> Result<String> {
+    let ttl = config
+        .model_cache_ttl_hours
+        .unwrap_or(model_manager::DEFAULT_CACHE_TTL_HOURS);
+    let Some(unknown) = model_manager::find_unknown_model(api_key, &model, ttl).await? else {
+        return Ok(model);
+    };
+    if !interactive || unknown.suggestions.is_empty() {
+        return Err(unknown.into());
+    }
+
+    output.status(format!(
+        "{} Model '{}' is not available. Closest matches:",
+        "⚠️ ".yellow(),
+        model.yellow()
+    ));
+    for (i, suggestion) in unknown.suggestions.iter().enumerate() {
+        output.status(format!("  {}. {}", i + 1, suggestion));
+    }
+    let choice = prompt_line(
+        &format!(
+            "\n🔁 Switch to (1-{}, Enter to abort): ",
+            unknown.suggestions.len()
+        ),
+        output,
+    )?;
+    let Some(replacement) = choice
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| unknown.suggestions.get(i))
+    else {
+        return Err(unknown.into());
+    };
+
+    output.status(format!(
+        "{} {} for this run. To keep it: autocommiter config set selected_model {}",
+        "✓ Using".green(),
+        replacement,
+        replacement
+    ));
+    Ok(replacement.clone())
+}
+
+/// Replays the last `commits` commits through each model and reports the
+/// messages next to the originals. Every model gets the same summary, sized
+/// for the smallest context window among them. Progress goes to stderr so
+/// the Markdown report can be redirected.
+async fn bench_models(
+    repo_path: Option<&str>,
+    commits: usize,
+    models: &[String],
+    report_path: Option<&str>,
+    profile: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let cwd = repo_path.unwrap_or(".");
+    if !git::is_git_repository(cwd) {
+        return Err(anyhow!("Not a git repository"));
+    }
+
+    let repo_root = git::get_repo_root(cwd)?;
+    let layered = config::load_layered_config(Some(&repo_root), profile)?;
+    let config = &layered.config;
+    let github_key = credentials::resolve_api_key(&layered)?.map(|k| k.key);
+
+    let models = match models {
+        [] => api_client::model_chain(
+            &config.effective_model(),
+            &config.effective_fallback_models(),
+        )?,
+        [first, rest @ ..] => api_client::model_chain(
+            &config.resolve_model(first),
+            &rest
+                .iter()
+                .map(|m| config.resolve_model(m))
+                .collect::<Vec<_>>(),
+        )?,
+    };
+    let history = git::get_recent_commits(&repo_root, commits)?;
+    if history.is_empty() {
+        return Err(anyhow!("No commits to replay"));
+    }
+
+    let mut budget = usize::MAX;
+    let mut infos = Vec::new();
+    for model in &models {
+        budget = budget.min(summary_budget_for(&model.to_string())?);
+        infos.push(model_manager::model_info(&model.to_string())?);
+    }
+
+    eprintln!(
+        "{} {} commit(s) through {} model(s)...",
+        "🏁 Replaying".cyan(),
+        history.len(),
+        models.len()
+    );
+    let mut replayed = Vec::new();
+    for commit in history {
+        let file_changes = changes_summarizer::build_commit_file_changes(&repo_root, &commit.sha)?;
+        let summary = summarize_file_changes(&repo_root, config, file_changes, budget)?;
+        let prompt = api_client::build_user_prompt(&summary.file_names, &summary.compressed_json);
+        eprintln!(
+            "  {} {}",
+            commit.sha[..commit.sha.len().min(7)].dimmed(),
+            commit.message.lines().next().unwrap_or_default()
+        );
+
+        let mut results = Vec::new();
+        for (model, info) in models.iter().zip(&infos) {
+            let mut result = bench::BenchResult {
+                model: model.to_string(),
+                message: None,
+                error: None,
+                latency_ms: None,
+                usage: None,
+                estimated_cost: None,
+            };
+            match credentials::provider_api_key(model.provider, github_key.as_deref()) {
+                None => result.error = Some(format!("no API key for {}", model.provider.name())),
+                Some(api_key) => {
+                    let started = std::time::Instant::now();
+                    let outcome = api_client::call_inference_api(
+                        &api_key,
+                        &prompt,
+                        model,
+                        api_client::GenerationOptions::default(),
+                    )
+                    .await;
+                    result.latency_ms = Some(started.elapsed().as_millis() as u64);
+                    match outcome {
+                        Ok(completion) => {
+                            result.estimated_cost =
+                                completion
+                                    .usage
+                                    .zip(info.as_ref())
+                                    .and_then(|(usage, info)| {
+                                        info.estimated_cost(
+                                            usage.prompt_tokens as usize,
+                                            usage.completion_tokens as usize,
+                                        )
+                                    });
+                            result.usage = completion.usage;
+                            result.message = Some(completion.content);
+                        }
+                        Err(e) => result.error = Some(e.to_string()),
+                    }
+                }
+            }
+
+            match (&result.message, &result.error) {
+                (Some(_), _) => eprintln!(
+                    "    {} {} ({} ms)",
+                    "✓".green(),
+                    model,
+                    result.latency_ms.unwrap_or_default()
+                ),
+                (None, error) => eprintln!(
+                    "    {} {}: {}",
+                    "✗".red(),
+                    model,
+                    error.as_deref().unwrap_or_default()
+                ),
+            }
+            results.push(result);
+        }
+
+        replayed.push(bench::BenchCommit {
+            sha: commit.sha,
+            original: commit.message,
+            results,
+        });
+    }
+
+    let report = bench::BenchReport::new(
+        repo_root,
+        models.iter().map(|m| m.to_string()).collect(),
+        replayed,
+    );
+    if let Some(path) = report_path {
+        let content = if path.ends_with(".json") {
+            serde_json::to_string_pretty(&report)?
+        } else {
+            bench::render_markdown(&report)
+        };
+        std::fs::write(path, content)?;
+        eprintln!("{} {}", "✓ Report written to".green(), path);
+    }
+
+    if output.is_json() {
+        let mut value = json!(report);
+        value["success"] = json!(true);
+        value["report_path"] = json!(report_path);
+        return output::print_json(&value);
+    }
+    if report_path.is_none() {
+        println!("{}", bench::render_markdown(&report));
+    }
     Ok(())
 }
 
-fn toggle_gitmoji() -> Result<()> {
-    let config = config::load_config()?;
-    let current = config.enable_gitmoji.unwrap_or(false);
-    let new_value = !current;
+async fn set_api_key(
+    key: Option<String>,
+    profile: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let key_to_set = if let Some(key) = key {
+        key
+    } else {
+        prompt_line(
+            "Enter GitHub API key (saved to a file only you can read): ",
+            output,
+        )?
+    };
+    let key_to_set = key_to_set.trim();
 
-    let mut new_config = config;
-    new_config.enable_gitmoji = Some(new_value);
-    config::save_config(&new_config)?;
+    if key_to_set.is_empty() {
+        return Err(anyhow!("API key cannot be empty"));
+    }
 
-    if new_value {
-        println!("{} {}", "✓ Gitmoji".green(), "enabled".green());
+    let repo_root = git::get_repo_root(".").ok();
+    let path = credentials::store_api_key(key_to_set, repo_root.as_deref(), profile)?;
+
+    // `api_key` and `api_key_command` are used before the key file
+    let layered = config::load_layered_config(repo_root.as_deref(), profile)?;
+    let overridden_by = ["api_key", "api_key_command"]
+        .into_iter()
+        .filter_map(|field| Some((field, *layered.sources.get(field)?)))
+        .find(|(_, source)| *source != config::ConfigSource::TokenEnv);
+
+    if output.is_json() {
+        return output::print_json(&json!({
+            "success": true,
+            "path": path.display().to_string(),
+            "overridden_by": overridden_by.map(|(field, source)| json!({ "field": field, "source": source })),
+        }));
+    }
+    println!("{}", "✓ API key saved!".green());
+    println!("  {}", format!("{} (mode 600)", path.display()).dimmed());
+    if let Some((field, source)) = overridden_by {
+        println!(
+            "{} {}",
+            "⚠️ ".yellow(),
+            format!(
+                "{} from the {} layer is used before the key file; unset it to use the new key",
+                field, source
+            )
+            .yellow()
+        );
+    }
+    Ok(())
+}
+
+fn get_api_key(profile: Option<&str>, output: OutputFormat) -> Result<()> {
+    let repo_root = git::get_repo_root(".").ok();
+    let key = credentials::get_api_key(repo_root.as_deref(), profile)?;
+    if output.is_json() {
+        return output::print_json(&json!({
+            "success": true,
+            "set": key.is_some(),
+            "api_key": key.as_ref().map(|k| output::mask_key(&k.key)),
+            "source": key.as_ref().map(|k| k.source),
+        }));
+    }
+
+    match key {
+        Some(key) => {
+            println!(
+                "{} {} {}",
+                "🔑 API Key:".cyan(),
+                output::mask_key(&key.key).yellow(),
+                format!("({})", key.source).dimmed()
+            );
+            Ok(())
+        }
+        None => {
+            println!(
+                "{}",
+                "ℹ️  No API key set. Use 'set-api-key' to add one.".yellow()
+            );
+            Ok(())
+        }
+    }
+}
+
+async fn refresh_models(profile: Option<&str>, output: OutputFormat) -> Result<()> {
+    let repo_root = git::get_repo_root(".").ok();
+    let api_key = credentials::get_api_key(repo_root.as_deref(), profile)?
+        .map(|k| k.key)
+        .ok_or_else(|| anyhow!("API key not set. Use 'set-api-key' first."))?;
+
+    output.status("🔄 Fetching models from GitHub Models API...".cyan());
+    let (success, message, count) = model_manager::refresh_model_list(&api_key).await?;
+
+    if output.is_json() {
+        return output::print_json(&json!({
+            "success": success,
+            "message": message,
+            "count": count,
+        }));
+    }
+
+    if success {
+        println!("{} {} models cached", "✓".green(), count);
     } else {
-        println!("{} {}", "✓ Gitmoji".green(), "disabled".yellow());
+        println!("{} {}", "✗".red(), message);
     }
     Ok(())
 }
 
-fn toggle_skip_confirmation() -> Result<()> {
-    let config = config::load_config()?;
-    let current = config.skip_confirmation.unwrap_or(false);
-    let new_value = !current;
+/// Model list, refreshed first when the cache is older than
+/// `model_cache_ttl_hours`.
+async fn load_model_list(layered: &config::LayeredConfig) -> Result<model_manager::CachedModels> {
+    let config = &layered.config;
+    let api_key = credentials::resolve_api_key(layered)?.map(|k| k.key);
+    model_manager::load_models(
+        api_key.as_deref(),
+        config
+            .model_cache_ttl_hours
+            .unwrap_or(model_manager::DEFAULT_CACHE_TTL_HOURS),
+    )
+    .await
+}
 
-    let mut new_config = config;
-    new_config.skip_confirmation = Some(new_value);
-    config::save_config(&new_config)?;
+/// Rough length of a generated commit message, for cost estimates.
+const EXPECTED_MESSAGE_TOKENS: usize = 40;
 
+/// Cost of one generation with `model` when the summary fills its budget.
+fn expected_commit_cost(model: &model_manager::ModelInfo) -> Option<f64> {
+    let budget = changes_summarizer::summary_budget(model.context_window, model.max_output_tokens);
+    let input_tokens = api_client::estimate_tokens(api_client::SYSTEM_PROMPT) + budget.div_ceil(4);
+    model.estimated_cost(input_tokens, EXPECTED_MESSAGE_TOKENS)
+}
+
+/// "128k context · 16k output · $0.15/$0.60 per 1M tokens"-style line, or
+/// `None` when nothing is known.
+fn format_capabilities(model: &model_manager::ModelInfo) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(window) = model.context_window {
+        parts.push(format!(
+            "{} context",
+            model_manager::format_token_count(window)
+        ));
+    }
+    if let Some(max_output) = model.max_output_tokens {
+        parts.push(format!(
+            "{} output",
+            model_manager::format_token_count(max_output)
+        ));
+    }
+    if let (Some(input), Some(output)) =
+        (model.input_cost_per_million, model.output_cost_per_million)
+    {
+        parts.push(format!("${:.2}/${:.2} per 1M tokens", input, output));
+    }
+    if let Some(cost) = expected_commit_cost(model) {
+        parts.push(format!("~{} per commit", model_manager::format_cost(cost)));
+    }
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+async fn list_models(
+    filter: Option<&str>,
+    publisher: Option<&str>,
+    tag: Option<&str>,
+    profile: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let repo_root = git::get_repo_root(".").ok();
+    let layered = config::load_layered_config(repo_root.as_deref(), profile)?;
+    let config = &layered.config;
+    let mut cached = load_model_list(&layered).await?;
+    // An alias filters for the model it names
+    let filter = filter.map(|f| config.resolve_model(f));
+    cached.models = model_manager::filter_models(&cached.models, filter.as_deref(), publisher, tag);
+    let current = config.effective_model();
+    let aliases = config.model_aliases.clone().unwrap_or_default();
+    let ttl = config
+        .model_cache_ttl_hours
+        .unwrap_or(model_manager::DEFAULT_CACHE_TTL_HOURS);
+    let stale = cached.fetched_at.is_some() && cached.is_stale(ttl);
+
+    if output.is_json() {
+        let models: Vec<serde_json::Value> = cached
+            .models
+            .iter()
+            .map(|m| {
+                let mut value = json!(m);
+                value["expected_cost_per_commit"] = json!(expected_commit_cost(m));
+                value["aliases"] = json!(config.aliases_for(&m.id));
+                value
+            })
+            .collect();
+        return output::print_json(&json!({
+            "success": true,
+            "selected_model": current,
+            "aliases": aliases,
+            "models": models,
+            "fetched_at": cached.fetched_at,
+            "source": cached.source,
+            "stale": stale,
+        }));
+    }
+
+    println!("{}", "📋 Available Models:".cyan().bold());
+    match (cached.fetched_at, &cached.source) {
+        (Some(fetched_at), Some(source)) => println!(
+            "{}",
+            format!(
+                "Fetched {} from {}{}",
+                model_manager::format_age(fetched_at),
+                source,
+                if stale { " (stale)" } else { "" }
+            )
+            .dimmed()
+        ),
+        _ => println!(
+            "{}",
+            "Built-in defaults; run 'refresh-models' or set an API key to fetch the full list"
+                .dimmed()
+        ),
+    }
+    println!();
+    if cached.models.is_empty() {
+        println!("{}", "ℹ️  No models match the given filters.".yellow());
+    }
+    for model in cached.models {
+        let marker = if model.id == current { "→" } else { " " };
+        let model_aliases = config.aliases_for(&model.id);
+        if model_aliases.is_empty() {
+            println!("{} {}", marker.green(), model.name.cyan());
+        } else {
+            println!(
+                "{} {} {}",
+                marker.green(),
+                model.name.cyan(),
+                format!("({})", model_aliases.join(", ")).yellow()
+            );
+        }
+        if let Some(friendly) = &model.friendly_name {
+            println!("   {}", friendly.dimmed());
+        }
+        if let Some(summary) = &model.summary {
+            println!("   {}", summary.dimmed());
+        }
+        if let Some(capabilities) = format_capabilities(&model) {
+            println!("   {}", capabilities.dimmed());
+        }
+        println!();
+    }
+
+    if !aliases.is_empty() {
+        println!("{}", "🏷️  Aliases:".cyan().bold());
+        for (alias, target) in &aliases {
+            println!("  {} → {}", alias.yellow(), target);
+        }
+    }
+    Ok(())
+}
+
+async fn select_model(
+    query: Option<&str>,
+    profile: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let repo_root = git::get_repo_root(".").ok();
+    let layered = config::load_layered_config(repo_root.as_deref(), profile)?;
+    let config = &layered.config;
+    let cached = load_model_list(&layered).await?;
+    let selected = match query {
+        // Aliases are stored as given so that redefining one takes effect
+        Some(alias) if config.resolve_model(alias) != alias => alias.to_string(),
+        Some(query) => resolve_model_query(query, &cached)?,
+        None => prompt_for_model(&cached.models, output)?,
+    };
+
+    let (path, key) = config::set_field(
+        repo_root.as_deref(),
+        profile,
+        "selected_model",
+        json!(selected),
+    )?;
+    let overridden_by = overriding_layer("selected_model", repo_root.as_deref(), profile)?;
+    if output.is_json() {
+        return output::print_json(&json!({
+            "success": true,
+            "selected_model": selected,
+            "key": key,
+            "path": path.display().to_string(),
+            "overridden_by": overridden_by,
+        }));
+    }
+    println!("{} {}", "✓ Selected:".green(), selected.cyan());
+    println!("  {}", format!("{} in {}", key, path.display()).dimmed());
+    warn_overridden(&key, overridden_by);
+    Ok(())
+}
+
+/// The layer that still supplies `field` after a setter wrote it for
+/// `profile`: anything above the profile when one was named, or above the
+/// global file otherwise.
+fn overriding_layer(
+    field: &str,
+    repo_root: Option<&str>,
+    profile: Option<&str>,
+) -> Result<Option<config::ConfigSource>> {
+    let written = match profile {
+        Some(_) => config::ConfigSource::Profile,
+        None => config::ConfigSource::Global,
+    };
+    let layered = config::load_layered_config(repo_root, profile)?;
+    Ok(layered
+        .sources
+        .get(field)
+        .copied()
+        .filter(|source| *source > written))
+}
+
+fn warn_overridden(key: &str, overridden_by: Option<config::ConfigSource>) {
+    if let Some(source) = overridden_by {
+        println!(
+            "{} {}",
+            "⚠️ ".yellow(),
+            format!(
+                "{} is still overridden by the {} layer ({})",
+                key,
+                source,
+                config::PRECEDENCE
+            )
+            .yellow()
+        );
+    }
+}
+
+/// The model ID `query` names: an exact ID, or the single best fuzzy match.
+/// IDs for other providers, and any ID when only the built-in defaults are
+/// known, are taken as given.
+fn resolve_model_query(query: &str, cached: &model_manager::CachedModels) -> Result<String> {
+    if api_client::ModelRef::parse(query)?.provider != api_client::Provider::Github {
+        return Ok(query.to_string());
+    }
+    if let Some(model) = cached.models.iter().find(|m| m.id == query) {
+        return Ok(model.id.clone());
+    }
+
+    let ranked: Vec<(u8, &model_manager::ModelInfo)> = cached
+        .models
+        .iter()
+        .filter_map(|m| Some((model_manager::match_rank(query, m)?, m)))
+        .collect();
+    let best: Vec<&model_manager::ModelInfo> = match ranked.iter().map(|(rank, _)| *rank).min() {
+        Some(best_rank) => ranked
+            .iter()
+            .filter(|(rank, _)| *rank == best_rank)
+            .map(|(_, m)| *m)
+            .collect(),
+        None => vec![],
+    };
+
+    match best.as_slice() {
+        [model] => Ok(model.id.clone()),
+        [] if cached.source.is_none() => Ok(query.to_string()),
+        [] => Err(model_manager::UnknownModelError {
+            model: query.to_string(),
+            suggestions: model_manager::closest_models(query, &cached.models, 3),
+        }
+        .into()),
+        several => Err(anyhow!(
+            "'{}' matches several models: {}. Use the full ID.",
+            query,
+            several
+                .iter()
+                .map(|m| m.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+fn prompt_for_model(models: &[model_manager::ModelInfo], output: OutputFormat) -> Result<String> {
+    if models.is_empty() {
+        return Err(anyhow!("No models available"));
+    }
+
+    output.status(format!("{}\n", "🤖 Select a Model:".cyan().bold()));
+    for (idx, model) in models.iter().enumerate() {
+        output.status(format!(
+            "{}. {} ({})",
+            idx + 1,
+            model.name.cyan(),
+            model.friendly_name.as_ref().unwrap_or(&model.name).dimmed()
+        ));
+    }
+
+    let input = prompt_line(&format!("\nEnter choice (1-{}): ", models.len()), output)?;
+    let choice: usize = input.parse().map_err(|_| anyhow!("Invalid choice"))?;
+
+    if choice < 1 || choice > models.len() {
+        return Err(anyhow!("Choice out of range"));
+    }
+    Ok(models[choice - 1].id.clone())
+}
+
+fn get_model(profile: Option<&str>, output: OutputFormat) -> Result<()> {
+    let config = config::load_current_config(profile)?;
+    let model = config
+        .selected_model
+        .clone()
+        .unwrap_or_else(|| "gpt-4o-mini".to_string());
+    let resolved = config.resolve_model(&model);
+    if output.is_json() {
+        return output::print_json(
+            &json!({ "success": true, "model": model, "resolved_model": resolved }),
+        );
+    }
+    if resolved == model {
+        println!("{} {}", "🤖 Current Model:".cyan(), model.yellow());
+    } else {
+        println!(
+            "{} {} → {}",
+            "🤖 Current Model:".cyan(),
+            model.yellow(),
+            resolved
+        );
+    }
+    Ok(())
+}
+
+/// Flips a boolean setting at its `config::field_target` and reports the
+/// new value, warning when a higher layer still overrides it.
+fn toggle_setting(
+    field: &str,
+    label: &str,
+    profile: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let repo_root = git::get_repo_root(".").ok();
+    let (path, key) = config::field_target(repo_root.as_deref(), profile, field)?;
+    // A profile that does not set the field inherits it from the global file
+    let current = match config::get_key(&config::read_config_file(&path)?, &key) {
+        Some(value) => value.as_bool().unwrap_or(false),
+        None => serde_json::to_value(config::load_global_config()?)?[field]
+            .as_bool()
+            .unwrap_or(false),
+    };
+    let new_value = !current;
+    config::set_field(repo_root.as_deref(), profile, field, json!(new_value))?;
+    let overridden_by = overriding_layer(field, repo_root.as_deref(), profile)?;
+
+    if output.is_json() {
+        return output::print_json(&json!({
+            "success": true,
+            field: new_value,
+            "key": key,
+            "path": path.display().to_string(),
+            "overridden_by": overridden_by,
+        }));
+    }
     if new_value {
-        println!("{} {}", "✓ Skip Confirmation".green(), "enabled".green());
+        println!("{} {}", format!("✓ {}", label).green(), "enabled".green());
     } else {
-        println!("{} {}", "✓ Skip Confirmation".green(), "disabled".yellow());
+        println!("{} {}", format!("✓ {}", label).green(), "disabled".yellow());
     }
+    if profile.is_some() {
+        println!("  {}", format!("{} in {}", key, path.display()).dimmed());
+    }
+    warn_overridden(&key, overridden_by);
     Ok(())
 }
 
-fn get_config() -> Result<()> {
-    let config = config::load_config()?;
+fn get_config(profile: Option<&str>, output: OutputFormat) -> Result<()> {
+    let repo_root = git::get_repo_root(".").ok();
+    let layered = config::load_layered_config(repo_root.as_deref(), profile)?;
+    let config = layered.config.clone();
+    let source = |field: &str| {
+        layered
+            .sources
+            .get(field)
+            .copied()
+            .unwrap_or(config::ConfigSource::Default)
+    };
+
+    // A failing key command should not hide the rest of the configuration
+    let api_key = credentials::resolve_api_key(&layered);
+
+    if output.is_json() {
+        return output::print_json(&json!({
+            "success": true,
+            "api_key": api_key.as_ref().ok().and_then(|k| k.as_ref()).map(|k| output::mask_key(&k.key)),
+            "api_key_source": api_key.as_ref().ok().and_then(|k| k.as_ref()).map(|k| k.source),
+            "selected_model": config.selected_model,
+            "enable_gitmoji": config.enable_gitmoji.unwrap_or(false),
+            "update_gitignore": config.update_gitignore.unwrap_or(false),
+            "skip_confirmation": config.skip_confirmation.unwrap_or(false),
+            "gitignore_patterns": config.gitignore_patterns.unwrap_or_default(),
+            "secret_allowlist": config.secret_allowlist.unwrap_or_default(),
+            "profile": config.profile,
+            "profiles": config.profiles.unwrap_or_default().keys().collect::<Vec<_>>(),
+            "sources": layered.sources,
+            "precedence": config::PRECEDENCE,
+            "files": layered
+                .files
+                .iter()
+                .map(|(source, path)| json!({ "source": source, "path": path.display().to_string() }))
+                .collect::<Vec<_>>(),
+        }));
+    }
+
+    let yes_no = |value: Option<bool>| {
+        if value.unwrap_or(false) {
+            "Yes".green()
+        } else {
+            "No".red()
+        }
+    };
+
     println!("{}\n", "⚙️  Configuration:".cyan().bold());
 
+    if let Some(profile) = &config.profile {
+        println!("{}:", "Profile".cyan());
+        println!("  {}\n", profile.yellow());
+    }
+
     println!("{}:", "API Key".cyan());
-    match &config.api_key {
-        Some(key) => {
-            let masked = if key.len() > 8 {
-                format!("{}...{}", &key[..4], &key[key.len() - 4..])
-            } else {
-                "****".to_string()
-            };
-            println!("  {}", masked.yellow());
-        }
-        None => println!("  {}", "Not set".dimmed()),
+    match &api_key {
+        Ok(Some(key)) => println!(
+            "  {} {}",
+            output::mask_key(&key.key).yellow(),
+            format!("({})", key.source).dimmed()
+        ),
+        Ok(None) => println!("  {}", "Not set".dimmed()),
+        Err(e) => println!("  {}", e.to_string().red()),
     }
 
     println!("\n{}:", "Selected Model".cyan());
-    println!("  {}", config.selected_model.unwrap_or_default().yellow());
+    println!(
+        "  {} {}",
+        config.selected_model.clone().unwrap_or_default().yellow(),
+        format!("({})", source("selected_model")).dimmed()
+    );
 
     println!("\n{}:", "Gitmoji Enabled".cyan());
     println!(
-        "  {}",
-        if config.enable_gitmoji.unwrap_or(false) {
-            "Yes".green()
-        } else {
-            "No".red()
-        }
+        "  {} {}",
+        yes_no(config.enable_gitmoji),
+        format!("({})", source("enable_gitmoji")).dimmed()
     );
 
     println!("\n{}:", "Update Gitignore".cyan());
     println!(
-        "  {}",
-        if config.update_gitignore.unwrap_or(false) {
-            "Yes".green()
-        } else {
-            "No".red()
-        }
+        "  {} {}",
+        yes_no(config.update_gitignore),
+        format!("({})", source("update_gitignore")).dimmed()
     );
 
     println!("\n{}:", "Skip Confirmation".cyan());
+    println!(
+        "  {} {}",
+        yes_no(config.skip_confirmation),
+        format!("({})", source("skip_confirmation")).dimmed()
+    );
+
+    println!("\n{}:", "Config Files".cyan());
+    if layered.files.is_empty() {
+        println!("  {}", "None (using defaults)".dimmed());
+    }
+    for (source, path) in &layered.files {
+        println!("  {} {}", format!("{}:", source).yellow(), path.display());
+    }
+    let env_overrides: Vec<&String> = layered
+        .sources
+        .iter()
+        .filter(|(_, source)| {
+            matches!(
+                source,
+                config::ConfigSource::Env | config::ConfigSource::TokenEnv
+            )
+        })
+        .map(|(field, _)| field)
+        .collect();
+    if !env_overrides.is_empty() {
+        println!(
+            "  {} {}",
+            "env:".yellow(),
+            env_overrides
+                .iter()
+                .map(|f| f.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
     println!(
         "  {}",
-        if config.skip_confirmation.unwrap_or(false) {
-            "Yes".green()
-        } else {
-            "No".red()
-        }
+        format!("Precedence: {}", config::PRECEDENCE).dimmed()
     );
 
     Ok(())
 }
 
-fn reset_config() -> Result<()> {
-    let default_config = config::Config::default();
-    config::save_config(&default_config)?;
+fn reset_config(profile: Option<&str>, output: OutputFormat) -> Result<()> {
+    if let Some(name) = profile {
+        return Err(anyhow!(
+            "reset-config replaces the whole global file and does not take --profile; remove single settings with `config unset profiles.{}.<field>`",
+            name
+        ));
+    }
+    let backup = config::reset_config()?;
+    if output.is_json() {
+        return output::print_json(&json!({ "success": true, "backup": backup }));
+    }
     println!("{}", "✓ Configuration reset to defaults!".green());
+    if let Some(backup) = backup {
+        println!(
+            "  Previous file kept at {}",
+            backup.display().to_string().dimmed()
+        );
+    }
+    Ok(())
+}
+
+fn config_command(action: ConfigAction, profile: Option<&str>, output: OutputFormat) -> Result<()> {
+    match action {
+        ConfigAction::Get { key, scope } => config_get(&key, scope.explicit(), profile, output),
+        ConfigAction::Set { key, value, scope } => {
+            let kind = config::require_writable_key(&key)?;
+            let field = config::split_profile_key(&key).map_or(key.as_str(), |(_, f)| f);
+            if field == "api_key" {
+                return Err(anyhow!(
+                    "The API key is not stored in config files; use `autocommiter set-api-key`, which writes a file only you can read"
+                ));
+            }
+            let value = config::parse_field_value(kind, &value)
+                .map_err(|e| anyhow!("Invalid value for {}: {}", key, e))?;
+            config_edit(&key, scope.for_write(), profile, output, |map| {
+                config::set_key(map, &key, value);
+                Ok(())
+            })
+        }
+        ConfigAction::Unset { key, scope } => {
+            config::require_writable_key(&key)?;
+            config_edit(&key, scope.for_write(), profile, output, |map| {
+                config::remove_key(map, &key);
+                Ok(())
+            })
+        }
+        ConfigAction::Add { key, values, scope } => {
+            config_edit_list(&key, scope.for_write(), profile, output, |list| {
+                for value in values {
+                    if !list.contains(&value) {
+                        list.push(value);
+                    }
+                }
+            })
+        }
+        ConfigAction::Remove { key, values, scope } => {
+            config_edit_list(&key, scope.for_write(), profile, output, |list| {
+                list.retain(|item| !values.contains(item))
+            })
+        }
+        ConfigAction::List { scope } => config_list(scope.explicit(), profile, output),
+        ConfigAction::Validate { scope } => config_validate(scope.explicit(), profile, output),
+    }
+}
+
+/// API keys are never printed in full.
+fn shown_config_value(key: &str, value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(k) if key == "api_key" => json!(output::mask_key(k)),
+        other => other.clone(),
+    }
+}
+
+fn format_config_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => "(not set)".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn config_get(
+    key: &str,
+    scope: Option<config::ConfigScope>,
+    profile: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    config::require_key(key)?;
+
+    if let Some(scope) = scope {
+        let path = config::get_scope_file(scope, ".")?;
+        let map = config::read_config_file(&path)?;
+        let value = config::get_key(&map, key)
+            .map(|v| shown_config_value(key, v))
+            .ok_or_else(|| anyhow!("{} is not set in the {} config", key, scope))?;
+        if output.is_json() {
+            return output::print_json(&json!({
+                "success": true,
+                "key": key,
+                "value": value,
+                "scope": scope,
+                "path": path.display().to_string(),
+            }));
+        }
+        println!("{}", format_config_value(&value));
+        return Ok(());
+    }
+
+    let repo_root = git::get_repo_root(".").ok();
+    let layered = config::load_layered_config(repo_root.as_deref(), profile)?;
+    let effective = serde_json::to_value(&layered.config)?;
+    let value = effective
+        .as_object()
+        .and_then(|map| config::get_key(map, key))
+        .map(|v| shown_config_value(key, v))
+        .unwrap_or(serde_json::Value::Null);
+    let source = layered.sources.get(key).copied();
+    if output.is_json() {
+        return output::print_json(
+            &json!({ "success": true, "key": key, "value": value, "source": source }),
+        );
+    }
+    println!("{}", format_config_value(&value));
+    Ok(())
+}
+
+/// Edits one field in the config file for `scope` and reports the result,
+/// including when a higher-precedence layer still overrides it.
+fn config_edit(
+    key: &str,
+    scope: config::ConfigScope,
+    profile: Option<&str>,
+    output: OutputFormat,
+    edit: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>) -> Result<()>,
+) -> Result<()> {
+    let path = config::get_scope_file(scope, ".")?;
+    config::update_config_file(&path, edit)?;
+
+    let stored = config::get_key(&config::read_config_file(&path)?, key)
+        .map(|v| shown_config_value(key, v))
+        .unwrap_or(serde_json::Value::Null);
+
+    // Settings inside a profile have no single effective source
+    let effective_source = match config::split_profile_key(key) {
+        Some(_) => None,
+        None => {
+            let repo_root = git::get_repo_root(".").ok();
+            let layered = config::load_layered_config(repo_root.as_deref(), profile)?;
+            Some(
+                layered
+                    .sources
+                    .get(key)
+                    .copied()
+                    .unwrap_or(config::ConfigSource::Default),
+            )
+        }
+    };
+    let scope_source = match scope {
+        config::ConfigScope::Global => config::ConfigSource::Global,
+        config::ConfigScope::Local => config::ConfigSource::Local,
+    };
+    let overridden = !stored.is_null() && effective_source.is_some_and(|s| s != scope_source);
+
+    if output.is_json() {
+        return output::print_json(&json!({
+            "success": true,
+            "key": key,
+            "value": stored,
+            "scope": scope,
+            "path": path.display().to_string(),
+            "effective_source": effective_source,
+        }));
+    }
+
+    if stored.is_null() {
+        println!(
+            "{}",
+            format!("✓ Unset {} in the {} config", key, scope).green()
+        );
+    } else {
+        println!(
+            "{}",
+            format!(
+                "✓ Set {} = {} in the {} config",
+                key,
+                format_config_value(&stored),
+                scope
+            )
+            .green()
+        );
+    }
+    println!("  {}", path.display().to_string().dimmed());
+    if overridden {
+        warn_overridden(key, effective_source);
+    }
     Ok(())
 }
 
-fn ensure_gitignore_safety(repo_root: &str) -> Result<()> {
-    let config = config::load_config()?;
+/// `config add/remove`: edits a list field, starting from the default list
+/// when the file does not set one yet.
+fn config_edit_list(
+    key: &str,
+    scope: config::ConfigScope,
+    profile: Option<&str>,
+    output: OutputFormat,
+    edit: impl FnOnce(&mut Vec<String>),
+) -> Result<()> {
+    if config::require_writable_key(key)? != config::FieldKind::List {
+        return Err(anyhow!(
+            "{} is not a list setting; use `config set` instead",
+            key
+        ));
+    }
+
+    config_edit(key, scope, profile, output, |map| {
+        let field = config::split_profile_key(key).map_or(key, |(_, field)| field);
+        // A list the file does not set yet starts from the value currently
+        // in effect for that scope: the defaults plus the global file, or
+        // every layer for the local override.
+        let current = match config::get_key(map, key) {
+            Some(value) => value.clone(),
+            None => {
+                let inherited = match scope {
+                    config::ConfigScope::Local => {
+                        config::load_effective_config(&git::get_repo_root(".")?, profile)?
+                    }
+                    config::ConfigScope::Global => config::load_global_config()?,
+                };
+                serde_json::to_value(inherited)?[field].clone()
+            }
+        };
+        let mut list: Vec<String> = serde_json::from_value(current).unwrap_or_default();
+        edit(&mut list);
+        config::set_key(map, key, json!(list));
+        Ok(())
+    })
+}
+
+/// Validates the file for `scope`, or every config file in effect plus the
+/// merged result when no scope is given.
+fn config_validate(
+    scope: Option<config::ConfigScope>,
+    profile: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let repo_root = git::get_repo_root(".").ok();
+    let mut files: Vec<(String, std::path::PathBuf)> = Vec::new();
+    match scope {
+        Some(scope) => files.push((scope.to_string(), config::get_scope_file(scope, ".")?)),
+        None => {
+            files.push(("global".to_string(), config::get_config_file()?));
+            if let Some(root) = &repo_root {
+                if let Some(repo_file) = config::get_repo_config_file(root) {
+                    files.push(("repo".to_string(), repo_file));
+                }
+                files.push(("local".to_string(), config::get_local_config_file(root)?));
+            }
+        }
+    }
+    files.retain(|(_, path)| path.exists());
+
+    let mut reports: Vec<(String, String, Vec<config::ValidationIssue>)> = files
+        .iter()
+        .map(|(source, path)| {
+            (
+                source.clone(),
+                path.display().to_string(),
+                config::validate_file(path),
+            )
+        })
+        .collect();
+
+    // Problems that only show up once layers and environment are combined,
+    // such as an undefined profile or a bad AUTOCOMMITER_* value
+    if scope.is_none() {
+        if let Err(e) = config::load_layered_config(repo_root.as_deref(), profile) {
+            reports.push((
+                "effective".to_string(),
+                "(merged configuration)".to_string(),
+                vec![config::ValidationIssue::error(None, e.to_string())],
+            ));
+        }
+    }
+
+    let errors = reports
+        .iter()
+        .flat_map(|(_, _, issues)| issues)
+        .filter(|issue| issue.level == "error")
+        .count();
+
+    if output.is_json() {
+        output::print_json(&json!({
+            "success": errors == 0,
+            "valid": errors == 0,
+            "files": reports
+                .iter()
+                .map(|(source, path, issues)| json!({ "source": source, "path": path, "issues": issues }))
+                .collect::<Vec<_>>(),
+        }))?;
+        if errors > 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if reports.is_empty() {
+        println!("{}", "ℹ️  No config files found; defaults apply.".yellow());
+        return Ok(());
+    }
+    for (source, path, issues) in &reports {
+        println!("{} {}", format!("{}:", source).cyan(), path.dimmed());
+        if issues.is_empty() {
+            println!("  {}", "✓ OK".green());
+        }
+        for issue in issues {
+            let location = issue
+                .field
+                .as_deref()
+                .map(|f| format!("{}: ", f))
+                .unwrap_or_default();
+            let line = format!("  {} {}{}", issue.level, location, issue.message);
+            if issue.level == "error" {
+                println!("{}", line.red());
+            } else {
+                println!("{}", line.yellow());
+            }
+        }
+    }
+
+    if errors > 0 {
+        return Err(anyhow!("Configuration has {} error(s)", errors));
+    }
+    Ok(())
+}
+
+fn config_list(
+    scope: Option<config::ConfigScope>,
+    profile: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    if let Some(scope) = scope {
+        let path = config::get_scope_file(scope, ".")?;
+        let values: serde_json::Map<String, serde_json::Value> = config::read_config_file(&path)?
+            .iter()
+            .map(|(key, value)| (key.clone(), shown_config_value(key, value)))
+            .collect();
+        if output.is_json() {
+            return output::print_json(&json!({
+                "success": true,
+                "scope": scope,
+                "path": path.display().to_string(),
+                "values": values,
+            }));
+        }
+        println!(
+            "{} {}",
+            format!("⚙️  {} config:", scope).cyan().bold(),
+            path.display().to_string().dimmed()
+        );
+        if values.is_empty() {
+            println!("  {}", "No settings".dimmed());
+        }
+        for (key, value) in &values {
+            println!("  {} = {}", key.yellow(), format_config_value(value));
+        }
+        return Ok(());
+    }
+
+    let repo_root = git::get_repo_root(".").ok();
+    let layered = config::load_layered_config(repo_root.as_deref(), profile)?;
+    let effective = serde_json::to_value(&layered.config)?;
+    let source = |key: &str| {
+        layered
+            .sources
+            .get(key)
+            .copied()
+            .unwrap_or(config::ConfigSource::Default)
+    };
+
+    if output.is_json() {
+        let values: serde_json::Map<String, serde_json::Value> = config::FIELDS
+            .iter()
+            .map(|(key, _)| (key.to_string(), shown_config_value(key, &effective[*key])))
+            .collect();
+        return output::print_json(
+            &json!({ "success": true, "values": values, "sources": layered.sources }),
+        );
+    }
+
+    for (key, _) in config::FIELDS {
+        println!(
+            "{} = {} {}",
+            key.yellow(),
+            format_config_value(&shown_config_value(key, &effective[*key])),
+            format!("({})", source(key)).dimmed()
+        );
+    }
+    Ok(())
+}
+
+fn install_hook(repo_path: Option<&str>, force: bool, output: OutputFormat) -> Result<()> {
+    let cwd = repo_path.unwrap_or(".");
+    if !git::is_git_repository(cwd) {
+        return Err(anyhow!("Not a git repository"));
+    }
+
+    let repo_root = git::get_repo_root(cwd)?;
+    let hook_path = hooks::install_hook(&repo_root, force)?;
+    if output.is_json() {
+        return output::print_json(&json!({
+            "success": true,
+            "installed": true,
+            "path": hook_path.display().to_string(),
+        }));
+    }
+    println!(
+        "{} {}",
+        "✓ Installed prepare-commit-msg hook:".green(),
+        hook_path.display().to_string().dimmed()
+    );
+    println!(
+        "{}",
+        "ℹ️  Plain `git commit` will now pre-fill the message from staged changes.".dimmed()
+    );
+    Ok(())
+}
+
+fn uninstall_hook(repo_path: Option<&str>, output: OutputFormat) -> Result<()> {
+    let cwd = repo_path.unwrap_or(".");
+    if !git::is_git_repository(cwd) {
+        return Err(anyhow!("Not a git repository"));
+    }
+
+    let repo_root = git::get_repo_root(cwd)?;
+    let removed = hooks::uninstall_hook(&repo_root)?;
+    if output.is_json() {
+        return output::print_json(&json!({ "success": true, "removed": removed }));
+    }
+    if removed {
+        println!("{}", "✓ Removed prepare-commit-msg hook".green());
+    } else {
+        println!("{}", "ℹ️  No prepare-commit-msg hook installed.".yellow());
+    }
+    Ok(())
+}
+
+async fn run_hook(msg_file: &str, source: Option<&str>, profile: Option<&str>) -> Result<()> {
+    // A failing hook aborts the user's commit, so problems are reported but
+    // never propagated.
+    if let Err(e) = fill_message_file(msg_file, source, profile).await {
+        eprintln!("{} {}", "⚠️  Autocommiter hook skipped:".yellow(), e);
+    }
+    Ok(())
+}
+
+async fn fill_message_file(
+    msg_file: &str,
+    source: Option<&str>,
+    profile: Option<&str>,
+) -> Result<()> {
+    let repo_root = git::get_repo_root(".")?;
+    let existing = std::fs::read_to_string(msg_file).unwrap_or_default();
+    let existing = if source == Some("template") {
+        // An untouched `commit.template` counts as no message; its text is
+        // kept as comments below the generated one.
+        let template = git::get_commit_template(&repo_root)
+            .and_then(|path| std::fs::read_to_string(path).ok());
+        match template {
+            Some(template) if hooks::is_unedited_template(&existing, &template) => {
+                hooks::comment_out_template(&existing)
+            }
+            _ => return Ok(()),
+        }
+    } else if hooks::should_generate_for_source(source) && hooks::is_message_empty(&existing) {
+        existing
+    } else {
+        return Ok(());
+    };
+
+    if git::get_staged_files(&repo_root)?.is_empty() {
+        return Ok(());
+    }
+    let layered = config::load_layered_config(Some(&repo_root), profile)?;
+    check_staged_secrets(&repo_root, &layered.config)?;
+
+    let github_key = credentials::resolve_api_key(&layered)?.map(|k| k.key);
+    let generated = generate_message(
+        &repo_root,
+        &layered.config,
+        github_key.as_deref(),
+        api_client::GenerationOptions::default(),
+        false,
+        OutputFormat::Text,
+    )
+    .await?;
+    // Without a generated message, leave the file alone so the editor opens
+    // with git's usual template rather than a placeholder.
+    if generated.model.is_none() {
+        return Ok(());
+    }
+    hooks::write_message_file(msg_file, &generated.message, &existing)
+}
+
+/// Lists tracked or staged files that match `gitignore_patterns` (for
+/// example a `.env` committed before the pattern was added) and, depending on
+/// `tracked_ignored_policy`, warns, aborts, or removes them from the index.
+/// Files matching `tracked_ignored_allowlist` are skipped.
+fn check_tracked_ignored_files(
+    repo_root: &str,
+    config: &config::Config,
+    interactive: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let patterns = config.gitignore_patterns.clone().unwrap_or_default();
+    if patterns.is_empty() {
+        return Ok(());
+    }
+
+    let kept = config.tracked_ignored_allowlist.clone().unwrap_or_default();
+    let offending: Vec<String> = git::get_index_files(repo_root)?
+        .into_iter()
+        .filter(|f| path_match::matches_any(&patterns, f) && !path_match::matches_any(&kept, f))
+        .collect();
+    if offending.is_empty() {
+        return Ok(());
+    }
+
+    output.status(format!(
+        "{} {} tracked file(s) match gitignore_patterns:",
+        "⚠️ ".yellow(),
+        offending.len()
+    ));
+    for file in &offending {
+        output.status(format!("  - {}", file.yellow()));
+    }
+
+    let policy = config.tracked_ignored_policy.as_deref().unwrap_or("prompt");
+    let action = match policy {
+        "prompt" if interactive => {
+            let answer = prompt_line(
+                "\n🧹 [u]ntrack them (git rm --cached), [k]eep (default), keep and [r]emember, or [a]bort? ",
+                output,
+            )?;
+            match answer.to_lowercase().as_str() {
+                "u" | "untrack" => "untrack",
+                "" | "k" | "keep" => "warn",
+                "r" | "remember" => "remember",
+                _ => "abort",
+            }
+        }
+        "prompt" | "warn" => "warn",
+        "abort" => "abort",
+        "untrack" => "untrack",
+        other => {
+            return Err(anyhow!(
+                "Invalid tracked_ignored_policy '{}': expected prompt, warn, abort or untrack",
+                other
+            ))
+        }
+    };
+
+    match action {
+        "untrack" => {
+            git::untrack_files(repo_root, &offending)?;
+            output.status(format!(
+                "{} Removed {} file(s) from the index; they stay on disk",
+                "✓".green(),
+                offending.len()
+            ));
+            Ok(())
+        }
+        "abort" => Err(anyhow!(
+            "Aborted: tracked files match gitignore_patterns. Untrack them with `git rm --cached <file>`."
+        )),
+        "remember" => {
+            let path = config::get_local_config_file(repo_root)?;
+            config::update_config_file(&path, |map| {
+                let mut list: Vec<String> = map
+                    .get("tracked_ignored_allowlist")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default();
+                for file in &offending {
+                    if !list.contains(file) {
+                        list.push(file.clone());
+                    }
+                }
+                map.insert("tracked_ignored_allowlist".to_string(), json!(list));
+                Ok(())
+            })?;
+            output.status(format!(
+                "{} Keeping them tracked; added to tracked_ignored_allowlist in {}",
+                "✓".green(),
+                path.display()
+            ));
+            Ok(())
+        }
+        _ => {
+            output.status(
+                "ℹ️  Keeping them tracked. Run `git rm --cached <file>` to stop tracking.".yellow(),
+            );
+            Ok(())
+        }
+    }
+}
+
+fn ensure_gitignore_safety(repo_root: &str, config: &config::Config) -> Result<()> {
     let should_update = config.update_gitignore.unwrap_or(false);
 
     if !should_update {
@@ -453,7 +2563,7 @@ fn ensure_gitignore_safety(repo_root: &str) -> Result<()> {
 
     let gitignore_path = std::path::Path::new(repo_root).join(".gitignore");
     let existing = std::fs::read_to_string(&gitignore_path).unwrap_or_default();
-    let patterns = config.gitignore_patterns.unwrap_or_default();
+    let patterns = config.gitignore_patterns.clone().unwrap_or_default();
 
     let lines: Vec<&str> = existing
         .lines()
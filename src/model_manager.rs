@@ -1,9 +1,24 @@
 use anyhow::{anyhow, Result};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fs;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Endpoint the model list is fetched from.
+pub const MODELS_ENDPOINT: &str = "https://models.inference.ai.azure.com/models";
+
+pub const DEFAULT_CACHE_TTL_HOURS: u64 = 24;
+
+/// Per-model capability overrides in the config directory.
+pub const OVERRIDES_FILE: &str = "models.toml";
+
+/// Upper bound for an automatic refresh, so a slow network never holds up a
+/// command for long; the stale cache is used instead.
+const AUTO_REFRESH_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ModelInfo {
     pub id: String,
     pub name: String,
@@ -12,57 +27,186 @@ pub struct ModelInfo {
     pub summary: Option<String>,
     pub task: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// Input plus output tokens the model accepts.
+    #[serde(default)]
+    pub context_window: Option<u64>,
+    #[serde(default)]
+    pub max_output_tokens: Option<u64>,
+    /// USD per million input tokens.
+    #[serde(default)]
+    pub input_cost_per_million: Option<f64>,
+    /// USD per million output tokens.
+    #[serde(default)]
+    pub output_cost_per_million: Option<f64>,
+}
+
+impl ModelInfo {
+    /// Cost in USD of a request of this size, when both prices are known.
+    pub fn estimated_cost(&self, input_tokens: usize, output_tokens: usize) -> Option<f64> {
+        let input = self.input_cost_per_million? * input_tokens as f64;
+        let output = self.output_cost_per_million? * output_tokens as f64;
+        Some((input + output) / 1_000_000.0)
+    }
+}
+
+/// Capabilities from `models.toml`, keyed by model ID (`provider:model` for
+/// providers other than GitHub Models). Set values replace what the API
+/// reported.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelOverride {
+    pub context_window: Option<u64>,
+    pub max_output_tokens: Option<u64>,
+    pub input_cost_per_million: Option<f64>,
+    pub output_cost_per_million: Option<f64>,
+}
+
+impl ModelOverride {
+    fn apply(&self, model: &mut ModelInfo) {
+        model.context_window = self.context_window.or(model.context_window);
+        model.max_output_tokens = self.max_output_tokens.or(model.max_output_tokens);
+        model.input_cost_per_million = self.input_cost_per_million.or(model.input_cost_per_million);
+        model.output_cost_per_million = self
+            .output_cost_per_million
+            .or(model.output_cost_per_million);
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CachedModels {
     pub models: Vec<ModelInfo>,
+    /// Unix time of the fetch; `None` for caches written by older versions
+    /// and for the built-in defaults.
+    #[serde(default)]
+    pub fetched_at: Option<u64>,
+    /// Endpoint the list came from; `None` for the built-in defaults.
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl CachedModels {
+    fn defaults() -> Self {
+        Self {
+            models: get_default_models(),
+            fetched_at: None,
+            source: None,
+        }
+    }
+
+    /// Older than `ttl_hours`, or of unknown age. A TTL of 0 never expires.
+    pub fn is_stale(&self, ttl_hours: u64) -> bool {
+        if ttl_hours == 0 {
+            return false;
+        }
+        match self.fetched_at {
+            Some(fetched_at) => now().saturating_sub(fetched_at) > ttl_hours * 3600,
+            None => true,
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// "128k"-style token count.
+pub fn format_token_count(tokens: u64) -> String {
+    match tokens {
+        t if t >= 1_000_000 && t % 1_000_000 == 0 => format!("{}M", t / 1_000_000),
+        t if t >= 1000 => format!("{}k", t / 1000),
+        t => t.to_string(),
+    }
+}
+
+/// "$0.00012"-style amount, keeping small per-request costs readable.
+pub fn format_cost(usd: f64) -> String {
+    if usd >= 0.01 {
+        format!("${:.2}", usd)
+    } else {
+        format!("${:.5}", usd)
+    }
+}
+
+/// "3h ago"-style age of a Unix timestamp.
+pub fn format_age(timestamp: u64) -> String {
+    let secs = now().saturating_sub(timestamp);
+    match secs {
+        s if s < 60 => "just now".to_string(),
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s if s < 86400 => format!("{}h ago", s / 3600),
+        s => format!("{}d ago", s / 86400),
+    }
 }
 
-const DEFAULT_MODELS: &[(&str, &str, &str)] = &[
+/// ID, friendly name, summary, context window and max output tokens.
+const DEFAULT_MODELS: &[(&str, &str, &str, u64, u64)] = &[
     (
         "gpt-4o-mini",
         "OpenAI GPT-4o mini",
         "Fast & cost-effective, great for most tasks",
+        128_000,
+        16_384,
     ),
     (
         "gpt-4o",
         "OpenAI GPT-4o",
         "High quality, most capable model",
+        128_000,
+        16_384,
     ),
     (
         "Phi-3-mini-128k-instruct",
         "Phi-3 mini 128k",
         "Lightweight, efficient open model",
+        128_000,
+        4_096,
     ),
     (
         "Mistral-large",
         "Mistral Large",
         "Powerful open-source model",
+        32_768,
+        4_096,
     ),
 ];
 
 fn get_default_models() -> Vec<ModelInfo> {
     DEFAULT_MODELS
         .iter()
-        .map(|(id, friendly_name, summary)| ModelInfo {
-            id: id.to_string(),
-            name: id.to_string(),
-            friendly_name: Some(friendly_name.to_string()),
-            publisher: None,
-            summary: Some(summary.to_string()),
-            task: Some("chat-completion".to_string()),
-            tags: None,
-        })
+        .map(
+            |(id, friendly_name, summary, context_window, max_output_tokens)| ModelInfo {
+                id: id.to_string(),
+                name: id.to_string(),
+                friendly_name: Some(friendly_name.to_string()),
+                publisher: None,
+                summary: Some(summary.to_string()),
+                task: Some("chat-completion".to_string()),
+                tags: None,
+                context_window: Some(*context_window),
+                max_output_tokens: Some(*max_output_tokens),
+                input_cost_per_million: None,
+                output_cost_per_million: None,
+            },
+        )
         .collect()
 }
 
+/// A token limit from a models API entry. Depending on the API version the
+/// limits sit at the top level or under `limits`/`model_limits`.
+fn token_limit(model: &Value, key: &str) -> Option<u64> {
+    std::iter::once(Some(model))
+        .chain(["limits", "model_limits"].iter().map(|o| model.get(o)))
+        .find_map(|obj| obj?.get(key)?.as_u64())
+}
+
 pub async fn fetch_available_models(api_key: &str) -> Result<Vec<ModelInfo>> {
     let client = Client::new();
-    let url = "https://models.inference.ai.azure.com/models";
 
     let response = client
-        .get(url)
+        .get(MODELS_ENDPOINT)
         .header("Accept", "application/vnd.github+json")
         .header("Authorization", format!("Bearer {}", api_key))
         .header("X-GitHub-Api-Version", "2022-11-28")
@@ -70,14 +214,10 @@ pub async fn fetch_available_models(api_key: &str) -> Result<Vec<ModelInfo>> {
         .await?;
 
     if !response.status().is_success() {
-        tracing::warn!(
-            "Failed to fetch models (status: {}), using defaults",
-            response.status()
-        );
-        return Ok(get_default_models());
+        return Err(anyhow!("models endpoint returned {}", response.status()));
     }
 
-    let models_response: Vec<serde_json::Value> = response.json().await?;
+    let models_response: Vec<Value> = response.json().await?;
     let models: Vec<ModelInfo> = models_response
         .iter()
         .filter_map(|m| {
@@ -106,6 +246,15 @@ pub async fn fetch_available_models(api_key: &str) -> Result<Vec<ModelInfo>> {
                             .filter_map(|tag| tag.as_str().map(|s| s.to_string()))
                             .collect()
                     }),
+                    context_window: token_limit(m, "context_window").or_else(|| {
+                        Some(
+                            token_limit(m, "max_input_tokens")?
+                                + token_limit(m, "max_output_tokens").unwrap_or(0),
+                        )
+                    }),
+                    max_output_tokens: token_limit(m, "max_output_tokens"),
+                    input_cost_per_million: None,
+                    output_cost_per_million: None,
                 })
             } else {
                 None
@@ -113,43 +262,294 @@ pub async fn fetch_available_models(api_key: &str) -> Result<Vec<ModelInfo>> {
         })
         .collect();
 
-    if models.is_empty() {
-        return Ok(get_default_models());
-    }
-
     Ok(models)
 }
 
+/// `models.json` in the XDG cache directory. A legacy
+/// `~/.autocommiter.models.json` is moved there on first use.
 fn get_models_cache_file() -> Result<std::path::PathBuf> {
-    let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not determine home directory"))?;
-    Ok(home.join(".autocommiter.models.json"))
+    let path = crate::paths::cache_dir()?.join("models.json");
+    crate::paths::migrate_legacy_file(
+        &crate::paths::home_dir()?.join(".autocommiter.models.json"),
+        &path,
+    )?;
+    Ok(path)
 }
 
-pub fn get_cached_models() -> Result<Vec<ModelInfo>> {
+/// The cache file, or `None` when it is missing or unreadable.
+pub fn load_cache() -> Result<Option<CachedModels>> {
     let cache_file = get_models_cache_file()?;
-
     if !cache_file.exists() {
-        return Ok(get_default_models());
+        return Ok(None);
     }
 
     let content = fs::read_to_string(&cache_file)?;
-    let cached: CachedModels = serde_json::from_str(&content).unwrap_or_else(|_| CachedModels {
-        models: get_default_models(),
-    });
-
-    Ok(cached.models)
+    Ok(serde_json::from_str(&content).ok())
 }
 
 pub fn update_cached_models(models: &[ModelInfo]) -> Result<()> {
     let cache_file = get_models_cache_file()?;
     let cached = CachedModels {
         models: models.to_vec(),
+        fetched_at: Some(now()),
+        source: Some(MODELS_ENDPOINT.to_string()),
     };
     let content = serde_json::to_string_pretty(&cached)?;
+    if let Some(parent) = cache_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
     fs::write(&cache_file, content)?;
     Ok(())
 }
 
+/// The model list, refreshed first when the cache is older than
+/// `ttl_hours` and an API key is available. When the refresh fails (for
+/// example offline) the stale cache, or the built-in defaults, is returned.
+pub async fn load_models(api_key: Option<&str>, ttl_hours: u64) -> Result<CachedModels> {
+    let mut cached = load_or_refresh(api_key, ttl_hours).await?;
+    let overrides = load_overrides()?;
+    for model in &mut cached.models {
+        if let Some(o) = overrides.get(&model.id) {
+            o.apply(model);
+        }
+    }
+    Ok(cached)
+}
+
+async fn load_or_refresh(api_key: Option<&str>, ttl_hours: u64) -> Result<CachedModels> {
+    let cached = load_cache()?;
+    if let Some(cached) = &cached {
+        if !cached.is_stale(ttl_hours) {
+            return Ok(cached.clone());
+        }
+    }
+
+    if let Some(api_key) = api_key {
+        if let Some(refreshed) = try_refresh(api_key).await? {
+            return Ok(refreshed);
+        }
+    }
+
+    Ok(cached.unwrap_or_else(CachedModels::defaults))
+}
+
+/// `models.toml` in the config directory, or no overrides when it is missing.
+pub fn load_overrides() -> Result<BTreeMap<String, ModelOverride>> {
+    let path = crate::paths::config_dir()?.join(OVERRIDES_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content = fs::read_to_string(&path)?;
+    toml::from_str(&content).map_err(|e| anyhow!("Invalid {}: {}", path.display(), e))
+}
+
+/// What is known about `spec` (a `selected_model` value) without touching
+/// the network: the cached list or built-in defaults, with overrides applied.
+/// Models missing from the list are described by their override alone.
+pub fn model_info(spec: &str) -> Result<Option<ModelInfo>> {
+    let overrides = load_overrides()?;
+    let cached = load_cache()?.unwrap_or_else(CachedModels::defaults);
+    let listed = cached
+        .models
+        .into_iter()
+        .find(|m| m.id == spec || m.name == spec);
+
+    let info = match (listed, overrides.get(spec)) {
+        (Some(mut model), Some(o)) => {
+            o.apply(&mut model);
+            Some(model)
+        }
+        (Some(model), None) => Some(model),
+        (None, Some(o)) => {
+            let mut model = ModelInfo {
+                id: spec.to_string(),
+                name: spec.to_string(),
+                ..Default::default()
+            };
+            o.apply(&mut model);
+            Some(model)
+        }
+        (None, None) => None,
+    };
+    Ok(info)
+}
+
+/// Fetches and caches the model list within `AUTO_REFRESH_TIMEOUT`. Returns
+/// `None`, after logging why, when the fetch fails.
+async fn try_refresh(api_key: &str) -> Result<Option<CachedModels>> {
+    match tokio::time::timeout(AUTO_REFRESH_TIMEOUT, fetch_available_models(api_key)).await {
+        Ok(Ok(models)) if !models.is_empty() => {
+            update_cached_models(&models)?;
+            return load_cache();
+        }
+        Ok(Ok(_)) => tracing::warn!("Model refresh returned no chat models; using cache"),
+        Ok(Err(e)) => tracing::warn!("Model refresh failed ({}); using cache", e),
+        Err(_) => tracing::warn!("Model refresh timed out; using cache"),
+    }
+    Ok(None)
+}
+
+/// `model` is not in the fetched model list.
+#[derive(Debug, thiserror::Error)]
+#[error("Model '{model}' is not available{}", format_suggestions(.suggestions))]
+pub struct UnknownModelError {
+    pub model: String,
+    /// Closest valid IDs, best first.
+    pub suggestions: Vec<String>,
+}
+
+fn format_suggestions(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        ". Run `autocommiter list-models` to see the available models.".to_string()
+    } else {
+        format!(
+            ". Did you mean: {}? Run `autocommiter select-model` to change it.",
+            suggestions.join(", ")
+        )
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, counted in chars.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            row[j + 1] = substitution.min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// Up to `limit` model IDs close to `id`, ignoring case. IDs that contain
+/// `id` (or are contained in it) always qualify; others must be within half
+/// its length in edits.
+pub fn closest_models(id: &str, models: &[ModelInfo], limit: usize) -> Vec<String> {
+    let needle = id.to_lowercase();
+    let max_distance = (needle.chars().count() / 2).max(2);
+
+    let mut scored: Vec<(usize, &str)> = models
+        .iter()
+        .filter_map(|m| {
+            let candidate = m.id.to_lowercase();
+            let distance = edit_distance(&needle, &candidate);
+            let related = candidate.contains(&needle) || needle.contains(&candidate);
+            (distance <= max_distance || related).then_some((distance, m.id.as_str()))
+        })
+        .collect();
+    scored.sort();
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, id)| id.to_string())
+        .collect()
+}
+
+/// Lowercase letters and digits only, so "gpt4o mini" matches "GPT-4o-mini".
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// How well `query` matches a model's ID, name or friendly name, ignoring
+/// case and punctuation: 0 for an exact match, 1 for a prefix, 2 for a
+/// substring and 3 when the query's characters appear in order. `None` when
+/// nothing matches.
+pub fn match_rank(query: &str, model: &ModelInfo) -> Option<u8> {
+    let query = normalize(query);
+    if query.is_empty() {
+        return Some(0);
+    }
+    std::iter::once(&model.id)
+        .chain(std::iter::once(&model.name))
+        .chain(model.friendly_name.as_ref())
+        .filter_map(|field| {
+            let field = normalize(field);
+            if field == query {
+                Some(0)
+            } else if field.starts_with(&query) {
+                Some(1)
+            } else if field.contains(&query) {
+                Some(2)
+            } else {
+                let mut chars = field.chars();
+                query.chars().all(|q| chars.any(|c| c == q)).then_some(3)
+            }
+        })
+        .min()
+}
+
+/// Models matching every given criterion, best text matches first.
+/// `publisher` and `tag` compare case-insensitively.
+pub fn filter_models(
+    models: &[ModelInfo],
+    text: Option<&str>,
+    publisher: Option<&str>,
+    tag: Option<&str>,
+) -> Vec<ModelInfo> {
+    let mut ranked: Vec<(u8, &ModelInfo)> = models
+        .iter()
+        .filter(|m| {
+            publisher.is_none_or(|p| {
+                m.publisher
+                    .as_deref()
+                    .is_some_and(|mp| mp.eq_ignore_ascii_case(p))
+            })
+        })
+        .filter(|m| {
+            tag.is_none_or(|t| m.tags.iter().flatten().any(|mt| mt.eq_ignore_ascii_case(t)))
+        })
+        .filter_map(|m| Some((text.map_or(Some(0), |q| match_rank(q, m))?, m)))
+        .collect();
+    // Stable, so equally good matches keep the list order
+    ranked.sort_by_key(|(rank, _)| *rank);
+    ranked.into_iter().map(|(_, m)| m.clone()).collect()
+}
+
+/// Checks `model` against the model list, refreshing a stale cache first.
+/// When it is missing from a cache that was not just fetched, the list is
+/// refreshed once more in case the model is new. Returns `None` when the
+/// model exists or only the built-in defaults are known, since those are not
+/// a complete list.
+pub async fn find_unknown_model(
+    api_key: &str,
+    model: &str,
+    ttl_hours: u64,
+) -> Result<Option<UnknownModelError>> {
+    let mut cached = load_models(Some(api_key), ttl_hours).await?;
+    let known = |cached: &CachedModels| {
+        cached
+            .models
+            .iter()
+            .any(|m| m.id == model || m.name == model)
+    };
+
+    if cached.source.is_none() || known(&cached) {
+        return Ok(None);
+    }
+    let just_fetched = cached
+        .fetched_at
+        .is_some_and(|t| now().saturating_sub(t) < 60);
+    if !just_fetched {
+        if let Some(refreshed) = try_refresh(api_key).await? {
+            cached = refreshed;
+            if known(&cached) {
+                return Ok(None);
+            }
+        }
+    }
+
+    Ok(Some(UnknownModelError {
+        model: model.to_string(),
+        suggestions: closest_models(model, &cached.models, 3),
+    }))
+}
+
 pub async fn refresh_model_list(api_key: &str) -> Result<(bool, String, usize)> {
     match fetch_available_models(api_key).await {
         Ok(models) => {
@@ -168,6 +568,89 @@ pub async fn refresh_model_list(api_key: &str) -> Result<(bool, String, usize)>
     }
 }
 
-pub fn list_available_models() -> Result<Vec<ModelInfo>> {
-    get_cached_models()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_staleness() {
+        let mut cached = CachedModels::defaults();
+        assert!(cached.is_stale(24));
+        assert!(!cached.is_stale(0));
+
+        cached.fetched_at = Some(now() - 3 * 3600);
+        assert!(!cached.is_stale(24));
+        assert!(cached.is_stale(2));
+        assert_eq!(format_age(now() - 3 * 3600), "3h ago");
+    }
+
+    #[test]
+    fn test_closest_models() {
+        assert_eq!(edit_distance("gpt-4o", "gpt-4o"), 0);
+        assert_eq!(edit_distance("gpt-4", "gpt-4o"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+
+        let models = CachedModels::defaults().models;
+        assert_eq!(
+            closest_models("gpt-4o-mni", &models, 3),
+            vec!["gpt-4o-mini", "gpt-4o"]
+        );
+        assert_eq!(closest_models("GPT-4o", &models, 1), vec!["gpt-4o"]);
+        assert!(closest_models("llama-70b", &models, 3).is_empty());
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let entry = serde_json::json!({
+            "name": "gpt-4o",
+            "task": "chat-completion",
+            "limits": {"max_input_tokens": 120000, "max_output_tokens": 8000},
+        });
+        assert_eq!(token_limit(&entry, "max_input_tokens"), Some(120_000));
+        assert_eq!(token_limit(&entry, "context_window"), None);
+
+        let overrides: BTreeMap<String, ModelOverride> = toml::from_str(
+            "[gpt-4o-mini]\ninput_cost_per_million = 0.15\noutput_cost_per_million = 0.6\n",
+        )
+        .unwrap();
+        let mut model = CachedModels::defaults().models.remove(0);
+        overrides["gpt-4o-mini"].apply(&mut model);
+        assert_eq!(model.context_window, Some(128_000));
+        let cost = model.estimated_cost(1_000_000, 1_000_000).unwrap();
+        assert!((cost - 0.75).abs() < 1e-9);
+
+        assert_eq!(format_token_count(128_000), "128k");
+        assert_eq!(format_token_count(2_000_000), "2M");
+    }
+
+    #[test]
+    fn test_filter_models() {
+        let mut models = CachedModels::defaults().models;
+        models[2].publisher = Some("Microsoft".to_string());
+        models[2].tags = Some(vec!["small".to_string()]);
+
+        let ids = |found: Vec<ModelInfo>| found.into_iter().map(|m| m.id).collect::<Vec<_>>();
+        assert_eq!(
+            ids(filter_models(&models, Some("gpt4o mini"), None, None)),
+            vec!["gpt-4o-mini"]
+        );
+        assert_eq!(
+            ids(filter_models(&models, Some("gpt-4o"), None, None)),
+            vec!["gpt-4o", "gpt-4o-mini"]
+        );
+        assert_eq!(
+            ids(filter_models(&models, Some("phi mini"), None, None)),
+            vec!["Phi-3-mini-128k-instruct"]
+        );
+        assert_eq!(
+            ids(filter_models(
+                &models,
+                None,
+                Some("microsoft"),
+                Some("SMALL")
+            )),
+            vec!["Phi-3-mini-128k-instruct"]
+        );
+        assert!(filter_models(&models, Some("llama"), None, None).is_empty());
+    }
 }
@@ -0,0 +1,104 @@
+use regex::Regex;
+
+/// Translates a gitignore-style glob into a regex over repo-relative paths.
+///
+/// - `*` and `?` do not cross `/`; `**` does.
+/// - A pattern without a `/` (other than a trailing one) matches at any depth;
+///   a leading `/` anchors it to the repository root.
+/// - A trailing `/` matches directories only. Any pattern that matches a
+///   directory also matches everything beneath it.
+pub fn glob_to_regex(pattern: &str) -> Option<Regex> {
+    let mut p = pattern.trim();
+    if p.is_empty() || p.starts_with('#') {
+        return None;
+    }
+
+    let dir_only = p.ends_with('/');
+    p = p.trim_end_matches('/');
+    let anchored = p.starts_with('/') || p.contains('/');
+    p = p.trim_start_matches('/');
+    if p.is_empty() {
+        return None;
+    }
+
+    let mut re = String::from("^");
+    if !anchored {
+        re.push_str("(?:.*/)?");
+    }
+
+    let chars: Vec<char> = p.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                if chars.get(i + 2) == Some(&'/') {
+                    re.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    re.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+
+    re.push_str(if dir_only { "/.*$" } else { "(?:/.*)?$" });
+    Regex::new(&re).ok()
+}
+
+pub fn matches(pattern: &str, path: &str) -> bool {
+    glob_to_regex(pattern).is_some_and(|re| re.is_match(path))
+}
+
+/// Gitignore semantics: patterns apply in order and a `!pattern` re-includes
+/// a path matched by an earlier one.
+pub fn matches_any(patterns: &[String], path: &str) -> bool {
+    patterns
+        .iter()
+        .fold(false, |matched, p| match p.trim().strip_prefix('!') {
+            Some(negated) => matched && !matches(negated, path),
+            None => matched || matches(p, path),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directory_patterns() {
+        assert!(matches("customers/", "customers/acme/contract.pdf"));
+        assert!(matches("customers/", "data/customers/list.csv"));
+        assert!(!matches("customers/", "customers.md"));
+        assert!(matches("/legal", "legal/nda.txt"));
+        assert!(!matches("/legal", "docs/legal/nda.txt"));
+    }
+
+    #[test]
+    fn test_wildcards() {
+        assert!(matches("*.env*", "config/prod.env.local"));
+        assert!(matches(".env*", ".env"));
+        assert!(!matches("src/*.rs", "src/bin/main.rs"));
+        assert!(matches("src/**/*.rs", "src/bin/main.rs"));
+        assert!(matches("src/**/*.rs", "src/main.rs"));
+        assert!(matches("file?.txt", "a/file1.txt"));
+    }
+
+    #[test]
+    fn test_negated_patterns() {
+        let patterns: Vec<String> = ["*.env*", "!.env.example", "!.env.sample"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert!(matches_any(&patterns, ".env"));
+        assert!(matches_any(&patterns, "api/.env.local"));
+        assert!(!matches_any(&patterns, ".env.example"));
+        assert!(!matches_any(&patterns, "api/.env.sample"));
+        assert!(!matches_any(&["!.env".to_string()], ".env"));
+    }
+}
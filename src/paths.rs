@@ -0,0 +1,89 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Overrides the location of the global config file.
+pub const CONFIG_ENV_VAR: &str = "AUTOCOMMITER_CONFIG";
+
+const APP_DIR: &str = "autocommiter";
+
+pub fn home_dir() -> Result<PathBuf> {
+    dirs::home_dir().ok_or_else(|| anyhow!("Could not determine home directory"))
+}
+
+/// `$var` when it holds an absolute path, otherwise `~/<fallback>`. Relative
+/// values are ignored, as the XDG base directory spec requires.
+fn xdg_dir(var: &str, fallback: &str) -> Result<PathBuf> {
+    match std::env::var_os(var).map(PathBuf::from) {
+        Some(dir) if dir.is_absolute() => Ok(dir),
+        _ => Ok(home_dir()?.join(fallback)),
+    }
+}
+
+/// `$XDG_CONFIG_HOME/autocommiter` (default `~/.config/autocommiter`).
+pub fn config_dir() -> Result<PathBuf> {
+    Ok(xdg_dir("XDG_CONFIG_HOME", ".config")?.join(APP_DIR))
+}
+
+/// `$XDG_CACHE_HOME/autocommiter` (default `~/.cache/autocommiter`).
+pub fn cache_dir() -> Result<PathBuf> {
+    Ok(xdg_dir("XDG_CACHE_HOME", ".cache")?.join(APP_DIR))
+}
+
+/// Moves a file from its old location in the home directory to `target` the
+/// first time the new location is used. An existing `target` is never
+/// replaced. The old `<name>.bak` is deleted rather than moved: it may still
+/// hold a plaintext API key.
+pub fn migrate_legacy_file(legacy: &Path, target: &Path) -> Result<()> {
+    if target.exists() || !legacy.exists() {
+        return Ok(());
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // `rename` fails across filesystems, e.g. when XDG_CONFIG_HOME is on
+    // another mount.
+    if fs::rename(legacy, target).is_err() {
+        fs::copy(legacy, target)?;
+        fs::remove_file(legacy)?;
+    }
+    tracing::warn!("Moved {} to {}", legacy.display(), target.display());
+
+    let mut backup = legacy.as_os_str().to_os_string();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+    if backup.exists() {
+        fs::remove_file(&backup)?;
+        tracing::warn!("Removed {}", backup.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_legacy_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let legacy = dir.path().join(".autocommiter.json");
+        let target = dir.path().join("config/autocommiter/config.json");
+
+        let backup = dir.path().join(".autocommiter.json.bak");
+        fs::write(&legacy, "{\"enable_gitmoji\": true}").unwrap();
+        fs::write(&backup, "{\"api_key\": \"ghp_old\"}").unwrap();
+        migrate_legacy_file(&legacy, &target).unwrap();
+        assert!(!legacy.exists());
+        assert!(!backup.exists());
+        assert_eq!(
+            fs::read_to_string(&target).unwrap(),
+            "{\"enable_gitmoji\": true}"
+        );
+
+        fs::write(&legacy, "{}").unwrap();
+        migrate_legacy_file(&legacy, &target).unwrap();
+        assert!(legacy.exists());
+        assert!(fs::read_to_string(&target).unwrap().contains("gitmoji"));
+    }
+}
@@ -0,0 +1,70 @@
+use crate::changes_summarizer::FileChange;
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::Path;
+
+/// Per-repository list of gitignore-style globs for paths the model must
+/// never see, not even as file names.
+pub const PRIVACY_FILE: &str = ".autocommiter-private";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrivacyMode {
+    /// Replace restricted files with a single "N restricted files modified" entry.
+    Placeholder,
+    /// Leave restricted files out of the payload entirely.
+    Exclude,
+}
+
+impl PrivacyMode {
+    pub fn parse(value: Option<&str>) -> Result<Self> {
+        match value.unwrap_or("placeholder") {
+            "placeholder" => Ok(PrivacyMode::Placeholder),
+            "exclude" => Ok(PrivacyMode::Exclude),
+            other => Err(anyhow!(
+                "Invalid privacy_mode '{}': expected 'placeholder' or 'exclude'",
+                other
+            )),
+        }
+    }
+}
+
+pub fn load_rules(repo_root: &str) -> Result<Vec<String>> {
+    let path = Path::new(repo_root).join(PRIVACY_FILE);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let content = fs::read_to_string(&path)?;
+    Ok(content
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.to_string())
+        .collect())
+}
+
+pub fn placeholder_text(count: usize) -> String {
+    if count == 1 {
+        "1 restricted file modified".to_string()
+    } else {
+        format!("{} restricted files modified", count)
+    }
+}
+
+/// Opaque stand-in for the withheld files in the summary payload.
+pub fn placeholder_entry(count: usize) -> FileChange {
+    FileChange {
+        file: "(restricted)".to_string(),
+        change: placeholder_text(count),
+    }
+}
+
+/// Splits off restricted files. Returns the changes that may be sent and the
+/// number of files that were withheld.
+pub fn apply_rules(changes: &[FileChange], rules: &[String]) -> (Vec<FileChange>, usize) {
+    let (restricted, visible): (Vec<FileChange>, Vec<FileChange>) = changes
+        .iter()
+        .cloned()
+        .partition(|fc| crate::path_match::matches_any(rules, &fc.file));
+    (visible, restricted.len())
+}
@@ -0,0 +1,156 @@
+use crate::changes_summarizer::FileChange;
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::ops::Range;
+
+lazy_static::lazy_static! {
+    static ref EMAIL: Regex =
+        Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap();
+    static ref IPV4: Regex = Regex::new(
+        r"\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\b"
+    )
+    .unwrap();
+    static ref IPV6: Regex = Regex::new(
+        r"\b(?:[0-9A-Fa-f]{1,4}:){7}[0-9A-Fa-f]{1,4}\b|\b(?:[0-9A-Fa-f]{1,4}:){1,6}:[0-9A-Fa-f]{1,4}\b"
+    )
+    .unwrap();
+}
+
+/// One value that was masked before the payload left the machine.
+#[derive(Debug, Clone, Serialize)]
+pub struct Redaction {
+    pub kind: String,
+    pub file: String,
+    /// `path` or `change`.
+    pub field: String,
+    /// Masked form of the original value.
+    pub original: String,
+}
+
+pub struct Redactor {
+    custom: Vec<Regex>,
+}
+
+impl Redactor {
+    pub fn new(custom_patterns: &[String]) -> Result<Self> {
+        let custom = custom_patterns
+            .iter()
+            .map(|p| {
+                Regex::new(p).map_err(|e| anyhow!("Invalid redact_patterns entry '{}': {}", p, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { custom })
+    }
+
+    fn find_matches(&self, text: &str) -> Vec<(String, Range<usize>)> {
+        let mut matches: Vec<(String, Range<usize>)> = crate::secret_scanner::find_secrets(text)
+            .into_iter()
+            .map(|(rule, range)| (rule.to_string(), range))
+            .collect();
+
+        let builtin: [(&str, &Regex); 3] = [("email", &EMAIL), ("ip", &IPV4), ("ip", &IPV6)];
+        for (kind, re) in builtin {
+            matches.extend(re.find_iter(text).map(|m| (kind.to_string(), m.range())));
+        }
+        for re in &self.custom {
+            matches.extend(
+                re.find_iter(text)
+                    .map(|m| ("custom".to_string(), m.range())),
+            );
+        }
+
+        // Keep the earliest (then longest) match where patterns overlap.
+        matches.sort_by(|a, b| a.1.start.cmp(&b.1.start).then(b.1.end.cmp(&a.1.end)));
+        let mut kept: Vec<(String, Range<usize>)> = Vec::new();
+        for (kind, range) in matches {
+            if range.is_empty() {
+                continue;
+            }
+            if kept.last().is_some_and(|(_, last)| range.start < last.end) {
+                continue;
+            }
+            kept.push((kind, range));
+        }
+        kept
+    }
+
+    /// Returns the redacted text and `(kind, original)` for every replacement.
+    pub fn redact_text(&self, text: &str) -> (String, Vec<(String, String)>) {
+        let matches = self.find_matches(text);
+        if matches.is_empty() {
+            return (text.to_string(), vec![]);
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut replaced = Vec::new();
+        let mut cursor = 0;
+        for (kind, range) in matches {
+            out.push_str(&text[cursor..range.start]);
+            out.push_str(&format!("[REDACTED:{}]", kind));
+            replaced.push((kind, text[range.clone()].to_string()));
+            cursor = range.end;
+        }
+        out.push_str(&text[cursor..]);
+        (out, replaced)
+    }
+
+    pub fn redact_file_changes(&self, changes: &[FileChange]) -> (Vec<FileChange>, Vec<Redaction>) {
+        let mut redactions = Vec::new();
+        let redacted = changes
+            .iter()
+            .map(|fc| {
+                let (file, in_path) = self.redact_text(&fc.file);
+                let (change, in_change) = self.redact_text(&fc.change);
+                for (field, found) in [("path", in_path), ("change", in_change)] {
+                    redactions.extend(found.into_iter().map(|(kind, original)| Redaction {
+                        kind,
+                        file: fc.file.clone(),
+                        field: field.to_string(),
+                        original: crate::secret_scanner::mask(&original),
+                    }));
+                }
+                FileChange { file, change }
+            })
+            .collect();
+        (redacted, redactions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacts_emails_ips_and_custom_patterns() {
+        let redactor = Redactor::new(&["ACME-\\d+".to_string()]).unwrap();
+        let (text, found) =
+            redactor.redact_text("mail jane@example.com from 10.0.0.12 about ACME-42");
+        assert_eq!(
+            text,
+            "mail [REDACTED:email] from [REDACTED:ip] about [REDACTED:custom]"
+        );
+        assert_eq!(found.len(), 3);
+    }
+
+    #[test]
+    fn test_redacts_file_paths_and_records_them() {
+        let redactor = Redactor::new(&[]).unwrap();
+        let changes = vec![
+            FileChange {
+                file: "customers/bob@corp.io/notes.md".to_string(),
+                change: "3+/0−".to_string(),
+            },
+            FileChange {
+                file: "src/main.rs".to_string(),
+                change: "1+/1−".to_string(),
+            },
+        ];
+        let (redacted, redactions) = redactor.redact_file_changes(&changes);
+        assert_eq!(redacted[0].file, "customers/[REDACTED:email]/notes.md");
+        assert_eq!(redacted[1].file, "src/main.rs");
+        assert_eq!(redactions.len(), 1);
+        assert_eq!(redactions[0].field, "path");
+        assert!(!redactions[0].original.contains("corp.io"));
+    }
+}